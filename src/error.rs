@@ -13,6 +13,12 @@ pub type Result<V> = std::result::Result<V, ZipError>;
 pub enum ZipError {
     #[error("Encountered an unexpected header (actual: {0:#x}, expected: {1:#x}).")]
     UnexpectedHeaderError(u32, u32),
+    #[error("Expected a {0} at offset {1:#x}, but found the signature {2:#010x}.")]
+    UnexpectedHeaderAt(&'static str, u64, u32),
+    #[error("The {0} at offset {1:#x} ended unexpectedly.")]
+    TruncatedHeader(&'static str, u64),
+    #[error("No {0} was found between offsets {1:#x} and {2:#x}.")]
+    HeaderNotFound(&'static str, u64, u64),
     #[error("{0} is not a supported compression type.")]
     UnsupportedCompressionError(u16),
     #[error("An upstream reader returned an error: '{0:?}'.")]
@@ -21,6 +27,24 @@ pub enum ZipError {
     FeatureNotSupported(&'static str),
     #[error("A computed CRC32 value did not match the expected value.")]
     CRC32CheckError,
+    #[error("The CRC32 value within the central directory ({0:#x}) did not match the data descriptor ({1:#x}).")]
+    DataDescriptorCRC32Mismatch(u32, u32),
     #[error("Entry index was out of bounds.")]
     EntryIndexOutOfBounds,
+    #[error("No entry named {0:?} exists within the archive.")]
+    EntryNotFound(String),
+    #[error("The provided password is incorrect for the entry.")]
+    IncorrectPassword,
+    #[error("The entry exceeds the {0} byte cap for buffering it in memory, so it should be streamed instead.")]
+    EntryTooLargeToBuffer(u64),
+    #[error("Seeking is only supported within stored entries of a known size read from a seekable source.")]
+    NotSeekable,
+    #[error("The extra field data exceeded the maximum length of 65535 bytes.")]
+    ExtraFieldTooLarge,
+    #[error("The archive is malformed: {0}.")]
+    InvalidArchive(&'static str),
+    #[error("The entry name {0:?} is not a safe path for extraction.")]
+    UnsafePath(String),
+    #[error("The entry {0:?} is a symbolic link, which the extraction options reject.")]
+    SymlinkRejected(String),
 }
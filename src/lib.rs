@@ -20,4 +20,6 @@ pub(crate) mod tests;
 pub(crate) mod utils;
 pub mod write;
 
-pub use crate::spec::compression::Compression;
+pub use crate::spec::compression::{Compression, DeflateOption};
+pub use crate::spec::extra_field::ExtraField;
+pub use crate::spec::version::HostSystem;
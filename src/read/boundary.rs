@@ -0,0 +1,172 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+// A reader which yields an entry's data up to its data descriptor, where the descriptor may lack its signature.
+//
+// The data descriptor's signature is optional, so it can't be relied upon as a delimiter. Instead, the end of an
+// entry's data is found by scanning for the signature of the record which follows it (ie. the next local file header
+// or the first central directory header), and then checking that the twelve bytes preceding it (or sixteen, if the
+// signature is present) form a descriptor whose compressed size matches the number of bytes before it. A ZIP64
+// descriptor holds eight-byte sizes, so the twenty (or twenty-four) preceding bytes are also checked in the same way.
+// A candidate which fails this check is data which happens to contain a signature, so the scan continues past it. The
+// descriptor's uncompressed size is checked by the entry reader once the data has been decompressed.
+//
+// https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#439
+
+use crate::spec::signature::{CENTRAL_DIRECTORY_FILE_HEADER, DATA_DESCRIPTOR, LOCAL_FILE_HEADER};
+
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use tokio::io::{AsyncRead, ReadBuf};
+
+// The number of trailing bytes which may still be part of a ZIP64 descriptor (with its signature) and a partially-read
+// signature of the following record, and so can't yet be yielded as data.
+const HOLD_BACK_LENGTH: u64 = 24 + 3;
+
+// The lengths of a descriptor without its signature, with four-byte and eight-byte (ie. ZIP64) sizes respectively.
+const DESCRIPTOR_LENGTHS: [(u64, bool); 2] = [(12, false), (20, true)];
+
+const READ_LENGTH: usize = 8192;
+
+pub(crate) struct BoundaryReader<R: AsyncRead + Unpin> {
+    inner: R,
+    // Bytes read from the inner reader but not yet yielded, starting at the absolute position `yielded`.
+    buffer: Vec<u8>,
+    yielded: u64,
+    scanned: u64,
+    boundary: Option<Boundary>,
+    eof: bool,
+}
+
+struct Boundary {
+    data_end: u64,
+    next_record: u64,
+    descriptor: (u32, u64, u64),
+}
+
+impl<R: AsyncRead + Unpin> BoundaryReader<R> {
+    pub(crate) fn new(inner: R) -> Self {
+        Self { inner, buffer: Vec::new(), yielded: 0, scanned: 0, boundary: None, eof: false }
+    }
+
+    pub(crate) fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    // Returns the CRC32 value, compressed size, and uncompressed size held within the data descriptor, once found.
+    pub(crate) fn descriptor(&self) -> Option<(u32, u64, u64)> {
+        self.boundary.as_ref().map(|boundary| boundary.descriptor)
+    }
+
+    // Takes any bytes which were read beyond the data descriptor (ie. the start of the following record), so that
+    // they can be prepended back onto the inner reader.
+    pub(crate) fn take_trailing(&mut self) -> Vec<u8> {
+        match &self.boundary {
+            Some(boundary) if self.yielded == boundary.data_end => {
+                self.buffer.split_off((boundary.next_record - self.yielded) as usize)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn u32_at(&self, position: u64) -> u32 {
+        let index = (position - self.yielded) as usize;
+        u32::from_le_bytes(self.buffer[index..index + 4].try_into().unwrap())
+    }
+
+    fn u64_at(&self, position: u64) -> u64 {
+        let index = (position - self.yielded) as usize;
+        u64::from_le_bytes(self.buffer[index..index + 8].try_into().unwrap())
+    }
+
+    // Returns the size field at the provided position, which is eight bytes wide within a ZIP64 descriptor.
+    fn size_at(&self, position: u64, zip64: bool) -> u64 {
+        match zip64 {
+            true => self.u64_at(position),
+            false => self.u32_at(position) as u64,
+        }
+    }
+
+    // Check whether the bytes preceding a following record's signature form a descriptor of either width.
+    fn check_candidate(&self, position: u64) -> Option<Boundary> {
+        for (length, zip64) in DESCRIPTOR_LENGTHS {
+            if position < length {
+                continue;
+            }
+
+            let fields = position - length;
+            let width = if zip64 { 8 } else { 4 };
+            let compressed = self.size_at(fields + 4, zip64);
+            let data_end = if fields >= 4 && self.u32_at(fields - 4) == DATA_DESCRIPTOR && compressed == fields - 4 {
+                fields - 4
+            } else if compressed == fields {
+                fields
+            } else {
+                continue;
+            };
+
+            let descriptor = (self.u32_at(fields), compressed, self.size_at(fields + 4 + width, zip64));
+            return Some(Boundary { data_end, next_record: position, descriptor });
+        }
+
+        None
+    }
+
+    // Scan every newly complete candidate position for the signature of a following record.
+    fn scan(&mut self) {
+        let total = self.yielded + self.buffer.len() as u64;
+
+        while self.boundary.is_none() && self.scanned + 4 <= total {
+            let position = self.scanned;
+            self.scanned += 1;
+
+            let signature = self.u32_at(position);
+            if signature == LOCAL_FILE_HEADER || signature == CENTRAL_DIRECTORY_FILE_HEADER {
+                self.boundary = self.check_candidate(position);
+            }
+        }
+    }
+
+    fn yield_bytes(&mut self, b: &mut ReadBuf<'_>, until: u64) {
+        let length = std::cmp::min((until - self.yielded) as usize, b.remaining());
+        b.put_slice(&self.buffer[..length]);
+        self.buffer.drain(..length);
+        self.yielded += length as u64;
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for BoundaryReader<R> {
+    fn poll_read(mut self: Pin<&mut Self>, c: &mut Context<'_>, b: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        loop {
+            self.scan();
+
+            if let Some(data_end) = self.boundary.as_ref().map(|boundary| boundary.data_end) {
+                self.yield_bytes(b, data_end);
+                return Poll::Ready(Ok(()));
+            }
+
+            let total = self.yielded + self.buffer.len() as u64;
+            let safe = total.saturating_sub(HOLD_BACK_LENGTH);
+
+            if safe > self.yielded {
+                self.yield_bytes(b, safe);
+                return Poll::Ready(Ok(()));
+            }
+
+            if self.eof {
+                let message = "no local file header or central directory header followed the entry's data";
+                return Poll::Ready(Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, message)));
+            }
+
+            let mut chunk = [0; READ_LENGTH];
+            let mut chunk_buf = ReadBuf::new(&mut chunk);
+            ready!(Pin::new(&mut self.inner).poll_read(c, &mut chunk_buf))?;
+
+            match chunk_buf.filled().len() {
+                0 => self.eof = true,
+                _ => self.buffer.extend_from_slice(chunk_buf.filled()),
+            }
+        }
+    }
+}
@@ -0,0 +1,240 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+//! A module which provides a caching layer over a seekable source.
+//!
+//! Fetched bytes are retained in fixed-size blocks, and the least recently used block is evicted once the cache is
+//! full. This is most useful over a remote (eg. HTTP range request backed) source where the same archive is browsed
+//! repeatedly, as the central directory and any previously read entries are then served without re-fetching.
+//!
+//! # Example
+//! ```no_run
+//! # use async_zip::read::cache::CachingSource;
+//! # use async_zip::read::seek::ZipFileReader;
+//! # use tokio::fs::File;
+//! # use async_zip::error::ZipError;
+//! #
+//! # async fn run() -> Result<(), ZipError> {
+//! let file = File::open("./Archive.zip").await.unwrap();
+//! let mut zip = ZipFileReader::new(CachingSource::new(file, 64)).await?;
+//!
+//! // Reading the same entry twice only fetches its data from the source once.
+//! zip.entry_reader(0).await?.read_to_string_crc().await?;
+//! zip.entry_reader(0).await?.read_to_string_crc().await?;
+//! #   Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::io::SeekFrom;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use tokio::io::{AsyncRead, AsyncSeek, ReadBuf};
+
+/// The default number of bytes held within each cached block.
+pub const DEFAULT_BLOCK_SIZE: usize = 16 * 1024;
+
+/// A set of statistics on how reads through a [`CachingSource`] were served.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// The number of reads served entirely from a cached block.
+    pub hits: u64,
+    /// The number of blocks which had to be fetched from the underlying source.
+    pub misses: u64,
+}
+
+/// A seekable source which caches the blocks it reads from an underlying seekable source.
+///
+/// # Note
+/// Seeking relative to the start of the source or the current position never touches the underlying source, so its
+/// own position is irrelevant; it's always sought to the start of a block before being read from.
+pub struct CachingSource<R: AsyncRead + AsyncSeek + Unpin> {
+    inner: R,
+    block_size: usize,
+    capacity: usize,
+    blocks: HashMap<u64, CachedBlock>,
+    ticks: u64,
+    position: u64,
+    seek: Option<PendingSeek>,
+    fetch: Option<PendingFetch>,
+    stats: CacheStats,
+}
+
+struct CachedBlock {
+    data: Vec<u8>,
+    last_used: u64,
+}
+
+enum PendingSeek {
+    Resolved(u64),
+    Inner,
+}
+
+struct PendingFetch {
+    index: u64,
+    buffer: Vec<u8>,
+    filled: usize,
+    sought: bool,
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> CachingSource<R> {
+    /// Constructs a new caching source which retains up to `capacity` blocks of the default block size.
+    pub fn new(inner: R, capacity: usize) -> Self {
+        Self::with_block_size(inner, capacity, DEFAULT_BLOCK_SIZE)
+    }
+
+    /// Constructs a new caching source which retains up to `capacity` blocks of `block_size` bytes each.
+    ///
+    /// Both the capacity and block size are raised to one if zero.
+    pub fn with_block_size(inner: R, capacity: usize, block_size: usize) -> Self {
+        Self {
+            inner,
+            block_size: std::cmp::max(block_size, 1),
+            capacity: std::cmp::max(capacity, 1),
+            blocks: HashMap::new(),
+            ticks: 0,
+            position: 0,
+            seek: None,
+            fetch: None,
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// Returns the statistics on how reads have been served so far.
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    /// Returns a mutable reference to the underlying source.
+    ///
+    /// Any data read via this reference won't be cached, and any changes to the data won't be reflected in blocks
+    /// which are already cached.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Consumes the caching source, returning the underlying source.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    // Fetch the block at the provided index from the underlying source and insert it into the cache.
+    fn poll_fetch(&mut self, c: &mut Context<'_>, index: u64) -> Poll<std::io::Result<()>> {
+        if self.fetch.as_ref().map(|fetch| fetch.index) != Some(index) {
+            Pin::new(&mut self.inner).start_seek(SeekFrom::Start(index * self.block_size as u64))?;
+            self.fetch = Some(PendingFetch { index, buffer: vec![0; self.block_size], filled: 0, sought: false });
+            self.stats.misses += 1;
+        }
+
+        let fetch = self.fetch.as_mut().unwrap();
+
+        if !fetch.sought {
+            ready!(Pin::new(&mut self.inner).poll_complete(c))?;
+            fetch.sought = true;
+        }
+
+        // A block shorter than the block size is only ever cached at the end of the source.
+        while fetch.filled < fetch.buffer.len() {
+            let mut buffer = ReadBuf::new(&mut fetch.buffer[fetch.filled..]);
+            ready!(Pin::new(&mut self.inner).poll_read(c, &mut buffer))?;
+
+            match buffer.filled().len() {
+                0 => break,
+                read => fetch.filled += read,
+            }
+        }
+
+        let mut fetch = self.fetch.take().unwrap();
+        fetch.buffer.truncate(fetch.filled);
+        self.insert(fetch.index, fetch.buffer);
+
+        Poll::Ready(Ok(()))
+    }
+
+    // Insert a block into the cache, evicting the least recently used block if the cache is full.
+    fn insert(&mut self, index: u64, data: Vec<u8>) {
+        if self.blocks.len() >= self.capacity {
+            let evicted = self.blocks.iter().min_by_key(|(_, block)| block.last_used).map(|(index, _)| *index);
+
+            if let Some(evicted) = evicted {
+                self.blocks.remove(&evicted);
+            }
+        }
+
+        self.blocks.insert(index, CachedBlock { data, last_used: self.ticks });
+    }
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> AsyncRead for CachingSource<R> {
+    fn poll_read(self: Pin<&mut Self>, c: &mut Context<'_>, b: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+
+        if b.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+
+        let index = this.position / this.block_size as u64;
+
+        if this.blocks.contains_key(&index) {
+            this.stats.hits += 1;
+        } else if let Err(err) = ready!(this.poll_fetch(c, index)) {
+            this.fetch = None;
+            return Poll::Ready(Err(err));
+        }
+
+        this.ticks += 1;
+        let block = this.blocks.get_mut(&index).unwrap();
+        block.last_used = this.ticks;
+
+        let offset = (this.position % this.block_size as u64) as usize;
+
+        if offset < block.data.len() {
+            let read = std::cmp::min(b.remaining(), block.data.len() - offset);
+            b.put_slice(&block.data[offset..offset + read]);
+            this.position += read as u64;
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> AsyncSeek for CachingSource<R> {
+    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> std::io::Result<()> {
+        let this = self.get_mut();
+        let invalid = || std::io::Error::new(std::io::ErrorKind::InvalidInput, "seek to a negative position");
+
+        // Any in-progress fetch is abandoned, as the underlying source would need to be sought again regardless.
+        this.fetch = None;
+        this.seek = Some(match position {
+            SeekFrom::Start(offset) => PendingSeek::Resolved(offset),
+            SeekFrom::Current(offset) => {
+                PendingSeek::Resolved(this.position.checked_add_signed(offset).ok_or_else(invalid)?)
+            }
+            SeekFrom::End(_) => {
+                Pin::new(&mut this.inner).start_seek(position)?;
+                PendingSeek::Inner
+            }
+        });
+
+        Ok(())
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, c: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        let this = self.get_mut();
+
+        match this.seek.take() {
+            Some(PendingSeek::Resolved(position)) => this.position = position,
+            Some(PendingSeek::Inner) => match Pin::new(&mut this.inner).poll_complete(c) {
+                Poll::Ready(result) => this.position = result?,
+                Poll::Pending => {
+                    this.seek = Some(PendingSeek::Inner);
+                    return Poll::Pending;
+                }
+            },
+            None => {}
+        }
+
+        Poll::Ready(Ok(this.position))
+    }
+}
@@ -25,8 +25,9 @@
 
 use super::CompressionReader;
 use crate::error::{Result, ZipError};
-use crate::read::{OwnedReader, PrependReader, ZipEntry, ZipEntryReader};
+use crate::read::{OwnedReader, PrependReader, ReaderOptions, ZipEntry, ZipEntryReader};
 use crate::spec::header::LocalFileHeader;
+use crate::spec::signature::LOCAL_FILE_HEADER;
 
 use async_io_utilities::AsyncDelimiterReader;
 use std::io::SeekFrom;
@@ -38,16 +39,22 @@ use tokio::io::{AsyncReadExt, AsyncSeekExt};
 pub struct ZipFileReader {
     pub(crate) filename: PathBuf,
     pub(crate) entries: Vec<ZipEntry>,
-    pub(crate) comment: Option<String>,
+    pub(crate) comment: Option<crate::read::ArchiveComment>,
+    pub(crate) layout: crate::read::ArchiveLayout,
 }
 
 impl ZipFileReader {
     /// Constructs a new ZIP file reader from a filename.
     pub async fn new<P: AsRef<Path>>(filename: P) -> Result<ZipFileReader> {
+        ZipFileReader::with_options(filename, ReaderOptions::default()).await
+    }
+
+    /// Constructs a new ZIP file reader from a filename and a set of options.
+    pub async fn with_options<P: AsRef<Path>>(filename: P, options: ReaderOptions) -> Result<ZipFileReader> {
         let mut fs_file = File::open(&filename).await?;
-        let (entries, comment) = crate::read::seek::read_cd(&mut fs_file).await?;
+        let (entries, comment, layout) = crate::read::seek::read_cd(&mut fs_file, &options).await?;
 
-        Ok(ZipFileReader { filename: filename.as_ref().to_path_buf(), entries, comment })
+        Ok(ZipFileReader { filename: filename.as_ref().to_path_buf(), entries, comment, layout })
     }
 
     crate::read::reader_entry_impl!();
@@ -55,11 +62,16 @@ impl ZipFileReader {
     /// Opens an entry at the provided index for reading.
     pub async fn entry_reader(&self, index: usize) -> Result<ZipEntryReader<'_, File>> {
         let entry = self.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
+        entry.assert_supported()?;
 
         let mut fs_file = File::open(&self.filename).await?;
-        fs_file.seek(SeekFrom::Start(entry.offset.unwrap() as u64 + 4)).await?;
+        let offset = entry.offset.unwrap();
+        fs_file.seek(SeekFrom::Start(offset)).await?;
 
-        let header = LocalFileHeader::from_reader(&mut fs_file).await?;
+        crate::utils::assert_signature_at(&mut fs_file, LOCAL_FILE_HEADER, offset).await?;
+        let header = LocalFileHeader::from_reader(&mut fs_file)
+            .await
+            .map_err(crate::utils::truncated_at(LOCAL_FILE_HEADER, offset))?;
         let data_offset = (header.file_name_length + header.extra_field_length) as i64;
         fs_file.seek(SeekFrom::Current(data_offset)).await?;
 
@@ -68,14 +80,14 @@ impl ZipFileReader {
             let reader = OwnedReader::Owned(fs_file);
             let reader = PrependReader::Normal(reader);
             let reader = AsyncDelimiterReader::new(reader, &delimiter);
-            let reader = CompressionReader::from_reader(entry.compression(), reader.take(u64::MAX));
+            let reader = CompressionReader::from_reader(entry, reader.take(u64::MAX));
 
             Ok(ZipEntryReader::with_data_descriptor(entry, reader, true))
         } else {
             let reader = OwnedReader::Owned(fs_file);
             let reader = PrependReader::Normal(reader);
-            let reader = reader.take(entry.compressed_size.unwrap().into());
-            let reader = CompressionReader::from_reader(entry.compression(), reader);
+            let reader = reader.take(entry.compressed_size.unwrap());
+            let reader = CompressionReader::from_reader(entry, reader);
 
             Ok(ZipEntryReader::from_raw(entry, reader, false))
         }
@@ -0,0 +1,101 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+//! A module which implements read-only decoders for the legacy Shrink and Reduce compression methods.
+//!
+//! Both methods predate streaming-friendly designs and are only found within very old archives, so the compressed
+//! data is buffered in full before being decoded in one pass.
+
+mod reduce;
+mod shrink;
+
+use crate::spec::compression::Compression;
+
+use std::io::Cursor;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::io::{AsyncRead, ReadBuf};
+
+// A reader over a byte slice which yields bits in least-significant-first order, as used by both methods.
+pub(crate) struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        BitReader { data, position: 0 }
+    }
+
+    // Read a value of up to 16 bits, or return None if fewer bits remain.
+    pub(crate) fn read(&mut self, bits: u8) -> Option<u16> {
+        let end = self.position + bits as usize;
+
+        if end > self.data.len() * 8 {
+            return None;
+        }
+
+        let mut value = 0;
+        for offset in 0..bits as usize {
+            let position = self.position + offset;
+            let bit = (self.data[position / 8] >> (position % 8)) & 1;
+            value |= (bit as u16) << offset;
+        }
+
+        self.position = end;
+        Some(value)
+    }
+}
+
+/// A reader which buffers all of its inner reader's compressed data and then serves the decoded data.
+pub(crate) struct LegacyDecoder<R: AsyncRead + Unpin> {
+    inner: R,
+    compression: Compression,
+    uncompressed_size: Option<u64>,
+    compressed: Vec<u8>,
+    decompressed: Option<Cursor<Vec<u8>>>,
+}
+
+impl<R: AsyncRead + Unpin> LegacyDecoder<R> {
+    pub(crate) fn new(inner: R, compression: Compression, uncompressed_size: Option<u64>) -> Self {
+        LegacyDecoder { inner, compression, uncompressed_size, compressed: Vec::new(), decompressed: None }
+    }
+
+    pub(crate) fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    fn decode(&self) -> std::io::Result<Vec<u8>> {
+        let decoded = match self.compression {
+            Compression::Shrink => shrink::unshrink(&self.compressed),
+            Compression::Reduce(factor) => reduce::unreduce(&self.compressed, factor, self.uncompressed_size),
+            _ => unreachable!(),
+        };
+
+        decoded.map_err(|message| std::io::Error::new(std::io::ErrorKind::InvalidData, message))
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for LegacyDecoder<R> {
+    fn poll_read(mut self: Pin<&mut Self>, c: &mut Context<'_>, b: &mut ReadBuf<'_>) -> Poll<tokio::io::Result<()>> {
+        while self.decompressed.is_none() {
+            let mut buffer = [0; async_io_utilities::SUGGESTED_BUFFER_SIZE];
+            let mut buffer = ReadBuf::new(&mut buffer);
+
+            match Pin::new(&mut self.inner).poll_read(c, &mut buffer) {
+                Poll::Ready(Ok(())) => {}
+                poll => return poll,
+            };
+
+            if buffer.filled().is_empty() {
+                let decompressed = self.decode()?;
+                self.decompressed = Some(Cursor::new(decompressed));
+            } else {
+                self.compressed.extend_from_slice(buffer.filled());
+            }
+        }
+
+        Pin::new(self.decompressed.as_mut().unwrap()).poll_read(c, b)
+    }
+}
@@ -0,0 +1,117 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+// https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#52
+//
+// Reduce is made up of two layers. The outer layer encodes each byte using a "follower set" of likely bytes for the
+// byte which preceded it. The inner layer is a simple LZ77 variant, where a DLE byte (144) introduces either a literal
+// DLE or a length/distance pair whose bit split depends upon the compression factor (between 1 and 4).
+
+use super::BitReader;
+
+const DLE: u8 = 144;
+const MAX_FOLLOWER_SET_LENGTH: u16 = 32;
+
+const TRUNCATED: &str = "truncated Reduce data";
+
+// The states of the inner LZ77 layer.
+enum State {
+    Literal,
+    Marker,
+    ExtraLength(u8, usize),
+    Distance(u8, usize),
+}
+
+// Decode Reduce data with the given compression factor, stopping once the uncompressed size has been reached. If the
+// size isn't known, decoding continues until the data is exhausted.
+pub(crate) fn unreduce(data: &[u8], factor: u8, size: Option<u64>) -> Result<Vec<u8>, &'static str> {
+    let mut reader = BitReader::new(data);
+    let mut follower_sets = vec![Vec::new(); 256];
+
+    // The follower sets are stored in descending order of their preceding byte.
+    for set in follower_sets.iter_mut().rev() {
+        let length = reader.read(6).ok_or(TRUNCATED)?;
+
+        if length > MAX_FOLLOWER_SET_LENGTH {
+            return Err("invalid Reduce follower set length");
+        }
+
+        for _ in 0..length {
+            set.push(reader.read(8).ok_or(TRUNCATED)? as u8);
+        }
+    }
+
+    let mask = 0xFF >> factor;
+    let mut output = Vec::new();
+    let mut state = State::Literal;
+    let mut last = 0;
+
+    while size.map_or(true, |size| (output.len() as u64) < size) {
+        let byte = match read_byte(&mut reader, &follower_sets[last as usize])? {
+            Some(byte) => byte,
+            None if size.is_none() => break,
+            None => return Err(TRUNCATED),
+        };
+
+        last = byte;
+        state = match state {
+            State::Literal if byte == DLE => State::Marker,
+            State::Literal => {
+                output.push(byte);
+                State::Literal
+            }
+            State::Marker if byte == 0 => {
+                output.push(DLE);
+                State::Literal
+            }
+            State::Marker if byte & mask == mask => State::ExtraLength(byte, mask as usize),
+            State::Marker => State::Distance(byte, (byte & mask) as usize),
+            State::ExtraLength(value, length) => State::Distance(value, length + byte as usize),
+            State::Distance(value, length) => {
+                let distance = (((value >> (8 - factor)) as usize) << 8) + byte as usize + 1;
+
+                // Bytes from before the start of the output are treated as zero.
+                for _ in 0..length + 3 {
+                    let byte = output.len().checked_sub(distance).map(|index| output[index]).unwrap_or_default();
+                    output.push(byte);
+                }
+
+                State::Literal
+            }
+        };
+    }
+
+    if let Some(size) = size {
+        output.truncate(size as usize);
+    }
+
+    Ok(output)
+}
+
+// Read the next byte of the outer layer given the follower set of the byte which preceded it.
+fn read_byte(reader: &mut BitReader<'_>, follower_set: &[u8]) -> Result<Option<u8>, &'static str> {
+    if follower_set.is_empty() {
+        return Ok(reader.read(8).map(|byte| byte as u8));
+    }
+
+    match reader.read(1) {
+        Some(1) => Ok(reader.read(8).map(|byte| byte as u8)),
+        Some(_) => match reader.read(index_width(follower_set.len())) {
+            Some(index) => follower_set.get(index as usize).copied().map(Some).ok_or("invalid Reduce follower index"),
+            None => Ok(None),
+        },
+        None => Ok(None),
+    }
+}
+
+// The number of bits used to store an index into a follower set of the given length.
+fn index_width(length: usize) -> u8 {
+    match length {
+        0 => 0,
+        1..=2 => 1,
+        3..=4 => 2,
+        5..=8 => 3,
+        9..=16 => 4,
+        _ => 5,
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+// https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#51
+//
+// Shrink is a dynamic LZW variant with codes of between 9 and 13 bits. Code 256 is reserved as a control code, which
+// is followed by either 1 (increase the code size by one bit) or 2 (partially clear the table by freeing every code
+// which isn't a prefix of another). New codes are always assigned the lowest free code.
+
+use super::BitReader;
+
+const CONTROL_CODE: u16 = 256;
+const FIRST_CODE: usize = 257;
+const MIN_CODE_SIZE: u8 = 9;
+const MAX_CODE_SIZE: u8 = 13;
+const TABLE_SIZE: usize = 1 << MAX_CODE_SIZE;
+const UNUSED: u16 = u16::MAX;
+
+const INVALID_CODE: &str = "invalid Shrink code";
+
+pub(crate) fn unshrink(data: &[u8]) -> Result<Vec<u8>, &'static str> {
+    let mut prefixes = vec![UNUSED; TABLE_SIZE];
+    let mut suffixes: Vec<u8> = (0..TABLE_SIZE).map(|code| code as u8).collect();
+    let mut reader = BitReader::new(data);
+    let mut code_size = MIN_CODE_SIZE;
+    let mut next_free = FIRST_CODE;
+    let mut output = Vec::new();
+    let mut string = Vec::new();
+
+    let mut previous = match reader.read(code_size) {
+        Some(code) if code < CONTROL_CODE => code,
+        Some(_) => return Err(INVALID_CODE),
+        None => return Ok(output),
+    };
+    output.push(previous as u8);
+
+    while let Some(code) = reader.read(code_size) {
+        if code == CONTROL_CODE {
+            match reader.read(code_size) {
+                Some(1) if code_size < MAX_CODE_SIZE => code_size += 1,
+                Some(2) => {
+                    partial_clear(&mut prefixes);
+                    next_free = FIRST_CODE;
+                }
+                _ => return Err("invalid Shrink control code"),
+            }
+            continue;
+        }
+
+        while next_free < TABLE_SIZE && prefixes[next_free] != UNUSED {
+            next_free += 1;
+        }
+
+        string.clear();
+
+        if code < CONTROL_CODE || prefixes[code as usize] != UNUSED {
+            expand(&prefixes, &suffixes, code, &mut string)?;
+        } else if code as usize == next_free {
+            // The code is the one about to be added, ie. the previous string followed by its own first byte.
+            expand(&prefixes, &suffixes, previous, &mut string)?;
+            string.push(string[0]);
+        } else {
+            return Err(INVALID_CODE);
+        }
+
+        output.extend_from_slice(&string);
+
+        if next_free < TABLE_SIZE {
+            prefixes[next_free] = previous;
+            suffixes[next_free] = string[0];
+            next_free += 1;
+        }
+
+        previous = code;
+    }
+
+    Ok(output)
+}
+
+// Expand a code into the string it represents by following its chain of prefixes.
+fn expand(prefixes: &[u16], suffixes: &[u8], mut code: u16, string: &mut Vec<u8>) -> Result<(), &'static str> {
+    loop {
+        string.push(suffixes[code as usize]);
+
+        if code < CONTROL_CODE {
+            break;
+        }
+
+        code = prefixes[code as usize];
+
+        // A freed prefix or a chain longer than the table (ie. a cycle) can only come from a malformed stream.
+        if code == UNUSED || string.len() > TABLE_SIZE {
+            return Err(INVALID_CODE);
+        }
+    }
+
+    string.reverse();
+    Ok(())
+}
+
+// Free every code which isn't a prefix of another code.
+fn partial_clear(prefixes: &mut [u16]) {
+    let mut is_prefix = vec![false; TABLE_SIZE];
+
+    for &prefix in &prefixes[FIRST_CODE..] {
+        if prefix != UNUSED {
+            is_prefix[prefix as usize] = true;
+        }
+    }
+
+    for code in FIRST_CODE..TABLE_SIZE {
+        if !is_prefix[code] {
+            prefixes[code] = UNUSED;
+        }
+    }
+}
@@ -4,8 +4,9 @@
 //! A module for reading ZIP file entries concurrently from an in-memory buffer.
 
 use crate::error::{Result, ZipError};
-use crate::read::{CompressionReader, OwnedReader, PrependReader, ZipEntry, ZipEntryReader};
+use crate::read::{CompressionReader, OwnedReader, PrependReader, ReaderOptions, ZipEntry, ZipEntryReader};
 use crate::spec::header::LocalFileHeader;
+use crate::spec::signature::LOCAL_FILE_HEADER;
 
 use std::io::{Cursor, SeekFrom};
 
@@ -19,14 +20,20 @@ pub type ConcurrentReader<'b, 'a> = ZipEntryReader<'b, Cursor<&'a [u8]>>;
 pub struct ZipFileReader<'a> {
     pub(crate) data: &'a [u8],
     pub(crate) entries: Vec<ZipEntry>,
-    pub(crate) comment: Option<String>,
+    pub(crate) comment: Option<crate::read::ArchiveComment>,
+    pub(crate) layout: crate::read::ArchiveLayout,
 }
 
 impl<'a> ZipFileReader<'a> {
     /// Constructs a new ZIP file reader from an in-memory buffer.
     pub async fn new(data: &'a [u8]) -> Result<ZipFileReader<'a>> {
-        let (entries, comment) = crate::read::seek::read_cd(&mut Cursor::new(data)).await?;
-        Ok(ZipFileReader { data, entries, comment })
+        ZipFileReader::with_options(data, ReaderOptions::default()).await
+    }
+
+    /// Constructs a new ZIP file reader from an in-memory buffer and a set of options.
+    pub async fn with_options(data: &'a [u8], options: ReaderOptions) -> Result<ZipFileReader<'a>> {
+        let (entries, comment, layout) = crate::read::seek::read_cd(&mut Cursor::new(data), &options).await?;
+        Ok(ZipFileReader { data, entries, comment, layout })
     }
 
     crate::read::reader_entry_impl!();
@@ -34,11 +41,16 @@ impl<'a> ZipFileReader<'a> {
     /// Opens an entry at the provided index for reading.
     pub async fn entry_reader<'b>(&'b mut self, index: usize) -> Result<ConcurrentReader<'b, 'a>> {
         let entry = self.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
+        entry.assert_supported()?;
 
         let mut cursor = Cursor::new(<&[u8]>::clone(&self.data));
-        cursor.seek(SeekFrom::Start(entry.offset.unwrap() as u64 + 4)).await?;
+        let offset = entry.offset.unwrap();
+        cursor.seek(SeekFrom::Start(offset)).await?;
 
-        let header = LocalFileHeader::from_reader(&mut cursor).await?;
+        crate::utils::assert_signature_at(&mut cursor, LOCAL_FILE_HEADER, offset).await?;
+        let header = LocalFileHeader::from_reader(&mut cursor)
+            .await
+            .map_err(crate::utils::truncated_at(LOCAL_FILE_HEADER, offset))?;
         let data_offset = (header.file_name_length + header.extra_field_length) as i64;
         cursor.seek(SeekFrom::Current(data_offset)).await?;
 
@@ -47,14 +59,14 @@ impl<'a> ZipFileReader<'a> {
             let reader = OwnedReader::Owned(cursor);
             let reader = PrependReader::Normal(reader);
             let reader = AsyncDelimiterReader::new(reader, &delimiter);
-            let reader = CompressionReader::from_reader(entry.compression(), reader.take(u64::MAX));
+            let reader = CompressionReader::from_reader(entry, reader.take(u64::MAX));
 
             Ok(ZipEntryReader::with_data_descriptor(entry, reader, true))
         } else {
             let reader = OwnedReader::Owned(cursor);
             let reader = PrependReader::Normal(reader);
-            let reader = reader.take(entry.compressed_size.unwrap().into());
-            let reader = CompressionReader::from_reader(entry.compression(), reader);
+            let reader = reader.take(entry.compressed_size.unwrap());
+            let reader = CompressionReader::from_reader(entry, reader);
 
             Ok(ZipEntryReader::from_raw(entry, reader, false))
         }
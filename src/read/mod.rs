@@ -3,25 +3,369 @@
 
 //! A module which supports reading ZIP files using various approaches.
 
+pub(crate) mod boundary;
+pub mod cache;
 pub mod fs;
+#[cfg(feature = "legacy")]
+pub(crate) mod legacy;
 pub mod mem;
 pub mod seek;
 pub mod stream;
 pub mod sync;
+pub(crate) mod zipcrypto;
 
 use crate::error::{Result, ZipError};
-use crate::spec::compression::Compression;
+use crate::spec::compression::{Compression, DeflateOption};
+use crate::spec::extra_field::ExtraField;
+use crate::spec::header::GeneralPurposeFlag;
+use crate::spec::version::HostSystem;
 
 use std::convert::TryInto;
+use std::io::{IoSliceMut, SeekFrom};
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
 #[cfg(any(feature = "deflate", feature = "bzip2", feature = "zstd", feature = "lzma", feature = "xz"))]
 use async_compression::tokio::bufread;
 use async_io_utilities::{AsyncDelimiterReader, AsyncPrependReader};
-use chrono::{DateTime, Utc};
+use boundary::BoundaryReader;
+use bytes::Bytes;
+use chrono::{DateTime, TimeZone, Utc};
 use crc32fast::Hasher;
-use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, BufReader, ReadBuf, Take};
+use encoding_rs::Encoding;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, AsyncWrite, BufReader, ReadBuf, Take};
+use zipcrypto::ZipCryptoReader;
+
+/// A set of options for opening ZIP file readers.
+#[derive(Clone, Default)]
+pub struct ReaderOptions {
+    pub(crate) capacity_hint: Option<usize>,
+    pub(crate) filename_encoding: Option<&'static Encoding>,
+    pub(crate) scan_central_directory: bool,
+    pub(crate) skip_comment_decoding: bool,
+    pub(crate) prefer_utf8: bool,
+}
+
+impl ReaderOptions {
+    /// Construct a new set of options with their default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Consume the options and override the initial capacity of the list of entries.
+    ///
+    /// By default, the capacity is computed from the entry count stored within the end of central directory record,
+    /// bounded by the maximum number of entries which could fit within the central directory. A hint is useful when
+    /// that count can't be trusted but the caller knows roughly how many entries to expect.
+    pub fn capacity_hint(mut self, capacity_hint: usize) -> Self {
+        self.capacity_hint = Some(capacity_hint);
+        self
+    }
+
+    /// Consume the options and set the encoding used for filenames and comments without the UTF-8 flag set.
+    ///
+    /// Many archivers (such as those on Japanese Windows systems) write filenames in a local encoding like Shift-JIS
+    /// without indicating as such. By default, these are still expected to be valid UTF-8. Entries which do have the
+    /// UTF-8 flag set are always decoded strictly as UTF-8.
+    ///
+    /// ```
+    /// # use async_zip::read::ReaderOptions;
+    /// let options = ReaderOptions::new().filename_encoding(encoding_rs::SHIFT_JIS);
+    /// ```
+    pub fn filename_encoding(mut self, encoding: &'static Encoding) -> Self {
+        self.filename_encoding = Some(encoding);
+        self
+    }
+
+    /// Consume the options and set whether to read central directory headers until the end of the central directory
+    /// is reached, rather than trusting the entry count stored within the end of central directory record.
+    ///
+    /// Some older archivers write more than 65535 entries without using ZIP64, so the stored count wraps around. This
+    /// mode salvages such archives by relying on the central directory's size instead. If no capacity hint is set,
+    /// the initial capacity is estimated from that size and the minimum length of each header.
+    pub fn scan_central_directory(mut self, scan_central_directory: bool) -> Self {
+        self.scan_central_directory = scan_central_directory;
+        self
+    }
+
+    /// Consume the options and set whether to skip decoding the archive's ending comment as a string.
+    ///
+    /// The end of central directory record is still fully validated, but the comment is retained only as raw bytes,
+    /// available via `comment_bytes()`. In this case, `comment()` always returns `None`.
+    pub fn skip_comment_decoding(mut self, skip_comment_decoding: bool) -> Self {
+        self.skip_comment_decoding = skip_comment_decoding;
+        self
+    }
+
+    /// Consume the options and set whether to first try decoding filenames and comments without the UTF-8 flag set
+    /// as UTF-8, only falling back to the encoding set via [`ReaderOptions::filename_encoding()`] if they're invalid.
+    ///
+    /// Many archivers write UTF-8 filenames without setting the flag, which a legacy encoding would decode as
+    /// mojibake. Bytes in a legacy encoding are rarely also valid UTF-8 (other than pure ASCII, which most legacy
+    /// encodings decode identically), so this heuristic is usually correct. It has no effect unless a fallback
+    /// encoding is set, as such names are otherwise always decoded as UTF-8.
+    pub fn prefer_utf8(mut self, prefer_utf8: bool) -> Self {
+        self.prefer_utf8 = prefer_utf8;
+        self
+    }
+
+    // Decodes a filename or comment, falling back to the configured encoding if the UTF-8 flag isn't set.
+    pub(crate) fn decode_string(&self, bytes: Vec<u8>, unicode: bool) -> Result<String> {
+        match self.filename_encoding {
+            Some(encoding) if !unicode => match String::from_utf8(bytes) {
+                Ok(decoded) if self.prefer_utf8 => Ok(decoded),
+                Ok(decoded) => Ok(encoding.decode_without_bom_handling(decoded.as_bytes()).0.into_owned()),
+                Err(err) => Ok(encoding.decode_without_bom_handling(err.as_bytes()).0.into_owned()),
+            },
+            _ => {
+                String::from_utf8(bytes).map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err).into())
+            }
+        }
+    }
+}
+
+// An archive's ending comment, which may be retained as raw bytes if decoding was skipped.
+pub(crate) enum ArchiveComment {
+    Decoded(String),
+    Raw(Vec<u8>),
+}
+
+impl ArchiveComment {
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            ArchiveComment::Decoded(comment) => Some(comment),
+            ArchiveComment::Raw(_) => None,
+        }
+    }
+
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        match self {
+            ArchiveComment::Decoded(comment) => comment.as_bytes(),
+            ArchiveComment::Raw(comment) => comment,
+        }
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        match self {
+            ArchiveComment::Decoded(comment) => comment.capacity(),
+            ArchiveComment::Raw(comment) => comment.capacity(),
+        }
+    }
+}
+
+// The layout of an archive's central directory and end of central directory record, as recorded when opened.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct ArchiveLayout {
+    pub(crate) eocdh_offset: u64,
+    pub(crate) cent_dir_offset: u64,
+    pub(crate) cent_dir_size: u64,
+    pub(crate) cent_dir_read: u64,
+}
+
+/// A structural issue found within an archive's metadata by a reader's `validate_structure()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StructureWarning {
+    /// An entry's local file header doesn't start before the central directory.
+    OffsetOutOfBounds { index: usize, offset: u64 },
+    /// An entry's local file header and compressed data overrun the start of the central directory.
+    DataOutOfBounds { index: usize, end: u64 },
+    /// An entry's local file header starts within the local file header or compressed data of another entry.
+    OverlappingEntries { first: usize, second: usize },
+    /// The size of the central directory recorded within the end of central directory record didn't match the
+    /// number of bytes its entries actually occupied.
+    CentralDirectorySizeMismatch { recorded: u64, actual: u64 },
+    /// The central directory overruns the end of central directory record.
+    CentralDirectoryOutOfBounds { end: u64, eocdh_offset: u64 },
+}
+
+// The length of a local file header (including its signature) with empty variable-length fields.
+const LOCAL_FILE_HEADER_MIN_LENGTH: u64 = 30;
+
+// Check an archive's parsed metadata for structural issues without reading any entry data.
+//
+// The region each entry occupies is taken as its fixed-length local file header plus its compressed data. This is a
+// lower bound, as the local header's variable-length fields aren't known without reading them, so only gross overlaps
+// are reported.
+pub(crate) fn validate_structure(
+    entries: &[ZipEntry],
+    layout: &ArchiveLayout,
+) -> std::result::Result<(), Vec<StructureWarning>> {
+    let mut warnings = Vec::new();
+    let cent_dir_end = layout.cent_dir_offset + layout.cent_dir_size;
+
+    if layout.cent_dir_size != layout.cent_dir_read {
+        let (recorded, actual) = (layout.cent_dir_size, layout.cent_dir_read);
+        warnings.push(StructureWarning::CentralDirectorySizeMismatch { recorded, actual });
+    }
+
+    if cent_dir_end > layout.eocdh_offset {
+        warnings.push(StructureWarning::CentralDirectoryOutOfBounds {
+            end: cent_dir_end,
+            eocdh_offset: layout.eocdh_offset,
+        });
+    }
+
+    let mut regions = Vec::with_capacity(entries.len());
+
+    for (index, entry) in entries.iter().enumerate() {
+        let (offset, compressed_size) = match (entry.offset, entry.compressed_size) {
+            (Some(offset), Some(compressed_size)) => (offset, compressed_size),
+            _ => continue,
+        };
+
+        if offset >= layout.cent_dir_offset {
+            warnings.push(StructureWarning::OffsetOutOfBounds { index, offset });
+            continue;
+        }
+
+        let end = offset.saturating_add(LOCAL_FILE_HEADER_MIN_LENGTH).saturating_add(compressed_size);
+
+        if end > layout.cent_dir_offset {
+            warnings.push(StructureWarning::DataOutOfBounds { index, end });
+        }
+
+        regions.push((offset, end, index));
+    }
+
+    regions.sort_unstable();
+
+    for pair in regions.windows(2) {
+        let ((_, first_end, first), (second_offset, _, second)) = (pair[0], pair[1]);
+
+        if second_offset < first_end {
+            warnings.push(StructureWarning::OverlappingEntries { first, second });
+        }
+    }
+
+    match warnings.is_empty() {
+        true => Ok(()),
+        false => Err(warnings),
+    }
+}
+
+// Returns the entries whose filename ends with a '.' followed by the provided extension, in order.
+//
+// Names are compared as bytes so that a multi-byte character preceding the extension can't cause a boundary panic.
+pub(crate) fn find_by_extension<'a>(
+    entries: &'a [ZipEntry],
+    ext: &str,
+    ignore_case: bool,
+) -> Vec<(usize, &'a ZipEntry)> {
+    let suffix = format!(".{}", ext);
+
+    let matches = |entry: &ZipEntry| {
+        let name = entry.name().as_bytes();
+
+        match name.len().checked_sub(suffix.len()).map(|start| &name[start..]) {
+            Some(tail) if ignore_case => tail.eq_ignore_ascii_case(suffix.as_bytes()),
+            Some(tail) => tail == suffix.as_bytes(),
+            None => false,
+        }
+    };
+
+    entries.iter().enumerate().filter(|(_, entry)| matches(entry)).collect()
+}
+
+/// A policy for handling entries which are symbolic links during extraction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SymlinkPolicy {
+    /// Recreate every symbolic link as-is, regardless of where its target points.
+    Recreate,
+    /// Recreate only symbolic links whose target resolves within the extraction root, and skip all others.
+    #[default]
+    SkipExternal,
+    /// Return an error upon encountering any symbolic link.
+    RejectAll,
+    /// Write each symbolic link as a regular file containing its target path.
+    AsRegularFile,
+}
+
+/// A set of options for extracting ZIP file entries to the filesystem.
+#[derive(Clone, Default)]
+pub struct ExtractOptions {
+    pub(crate) symlink_policy: SymlinkPolicy,
+    pub(crate) convert_text_line_endings: bool,
+}
+
+impl ExtractOptions {
+    /// Construct a new set of options with their default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Consume the options and override the policy for handling symbolic links.
+    ///
+    /// Symbolic links within untrusted archives may point anywhere on the filesystem, so this defaults to
+    /// [`SymlinkPolicy::SkipExternal`].
+    pub fn symlink_policy(mut self, symlink_policy: SymlinkPolicy) -> Self {
+        self.symlink_policy = symlink_policy;
+        self
+    }
+
+    /// Consume the options and set whether to convert the line endings of text entries to the platform's default
+    /// (ie. CRLF on Windows and LF elsewhere), as with `unzip -a`.
+    ///
+    /// Only entries flagged as text (see [`ZipEntry::is_text()`]) are converted, so binary data is never altered. A
+    /// lone CR is left as-is, and the entry's CRC32 value is still checked against its data before conversion.
+    pub fn convert_text_line_endings(mut self, convert_text_line_endings: bool) -> Self {
+        self.convert_text_line_endings = convert_text_line_endings;
+        self
+    }
+}
+
+/// A report of what an extraction would write, as computed without touching the filesystem.
+#[derive(Clone, Debug, Default)]
+pub struct ExtractionPlan {
+    pub(crate) files: Vec<PathBuf>,
+    pub(crate) directories: Vec<PathBuf>,
+    pub(crate) symlinks: Vec<PathBuf>,
+    pub(crate) skipped_symlinks: Vec<PathBuf>,
+    pub(crate) permissions: Vec<(PathBuf, u32)>,
+    pub(crate) rejected: Vec<String>,
+    pub(crate) total_bytes: u64,
+}
+
+impl ExtractionPlan {
+    /// Returns the paths of the regular files which would be written.
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// Returns the paths of the directories which would be created for directory entries.
+    pub fn directories(&self) -> &[PathBuf] {
+        &self.directories
+    }
+
+    /// Returns the paths of the symbolic links which would be recreated.
+    pub fn symlinks(&self) -> &[PathBuf] {
+        &self.symlinks
+    }
+
+    /// Returns the paths of the symbolic links which would be skipped under [`SymlinkPolicy::SkipExternal`], as their
+    /// targets resolve outside of the destination.
+    pub fn skipped_symlinks(&self) -> &[PathBuf] {
+        &self.skipped_symlinks
+    }
+
+    /// Returns the paths of files which would have their Unix permissions set, alongside those permissions.
+    pub fn permissions(&self) -> &[(PathBuf, u32)] {
+        &self.permissions
+    }
+
+    /// Returns the names of entries which would be rejected, either due to an unsafe path (including one which passes
+    /// through a symbolic link recreated earlier) or the symbolic link policy.
+    ///
+    /// An extraction aborts upon the first rejected entry, so a non-empty list means it would fail.
+    pub fn rejected(&self) -> &[String] {
+        &self.rejected
+    }
+
+    /// Returns the total number of uncompressed bytes which would be written to regular files.
+    pub fn total_bytes(&self) -> u64 {
+        self.total_bytes
+    }
+}
 
 /// An entry within a larger ZIP file reader.
 #[derive(Debug)]
@@ -30,14 +374,21 @@ pub struct ZipEntry {
     pub(crate) comment: Option<String>,
     pub(crate) data_descriptor: bool,
     pub(crate) crc32: Option<u32>,
-    pub(crate) uncompressed_size: Option<u32>,
-    pub(crate) compressed_size: Option<u32>,
+    pub(crate) uncompressed_size: Option<u64>,
+    pub(crate) compressed_size: Option<u64>,
     pub(crate) last_modified: DateTime<Utc>,
+    // The raw MS-DOS time, as held within the header, which is needed as-is for checking an encryption header.
+    pub(crate) mod_time: u16,
     pub(crate) extra: Option<Vec<u8>>,
     pub(crate) compression: Compression,
+    pub(crate) version_needed: u16,
+    pub(crate) flags: GeneralPurposeFlag,
 
     // Additional fields from EOCDH.
-    pub(crate) offset: Option<u32>,
+    pub(crate) offset: Option<u64>,
+    pub(crate) version_made_by: Option<u16>,
+    pub(crate) internal_attributes: Option<u16>,
+    pub(crate) external_attributes: Option<u32>,
 }
 
 impl ZipEntry {
@@ -54,6 +405,11 @@ impl ZipEntry {
         }
     }
 
+    /// Returns whether or not the entry's data is encrypted.
+    pub fn encrypted(&self) -> bool {
+        self.flags.encrypted
+    }
+
     /// Returns whether or not a data descriptor exists for the entry (ie. whether or not it was stream written).
     pub fn data_descriptor(&self) -> bool {
         self.data_descriptor
@@ -64,18 +420,60 @@ impl ZipEntry {
         self.name.ends_with('/')
     }
 
+    /// Returns whether or not the entry's name contains any control characters (eg. NUL or newline characters).
+    ///
+    /// Such names are commonly used to disguise the true name of an entry, as any characters following a NUL may be
+    /// hidden when the name is displayed or passed to the filesystem.
+    pub fn has_control_chars(&self) -> bool {
+        self.name.chars().any(char::is_control)
+    }
+
+    /// Returns the entry's name as a relative path which is safe to join onto an extraction directory.
+    ///
+    /// A [`ZipError::UnsafePath`] error is returned if the name contains control characters, is absolute, or contains
+    /// any parent directory (`..`) components. On Windows, components containing a colon (ie. drive prefixes and
+    /// alternate data streams) are also rejected. Empty and current directory (`.`) components are dropped, and both
+    /// forward and back slashes are treated as separators.
+    pub fn safe_path(&self) -> Result<PathBuf> {
+        let unsafe_path = || ZipError::UnsafePath(self.name.clone());
+
+        if self.has_control_chars() || self.name.starts_with('/') || self.name.starts_with('\\') {
+            return Err(unsafe_path());
+        }
+
+        let mut path = PathBuf::new();
+
+        for component in self.name.split(|c: char| c == '/' || c == '\\') {
+            match component {
+                "" | "." => continue,
+                ".." => return Err(unsafe_path()),
+                _ if cfg!(windows) && component.contains(':') => return Err(unsafe_path()),
+                _ => path.push(component),
+            }
+        }
+
+        Ok(path)
+    }
+
     /// Returns an optional CRC32 value for the entry.
+    ///
+    /// This will be `None` for entries read from a local file header which defer their CRC32 value to a data
+    /// descriptor (ie. when using the stream reader).
     pub fn crc32(&self) -> Option<u32> {
         self.crc32
     }
 
     /// Returns an optional compressed file size for the entry.
-    pub fn compressed_size(&self) -> Option<u32> {
+    ///
+    /// This will be `None` under the same conditions as [`ZipEntry::crc32()`].
+    pub fn compressed_size(&self) -> Option<u64> {
         self.compressed_size
     }
 
     /// Returns an optional uncompressed file size for the entry.
-    pub fn uncompressed_size(&self) -> Option<u32> {
+    ///
+    /// This will be `None` under the same conditions as [`ZipEntry::crc32()`].
+    pub fn uncompressed_size(&self) -> Option<u64> {
         self.uncompressed_size
     }
 
@@ -84,20 +482,184 @@ impl ZipEntry {
         &self.last_modified
     }
 
+    /// Returns the entry's creation time if it was recorded within an NTFS extra field.
+    pub fn created(&self) -> Option<DateTime<Utc>> {
+        // The number of seconds between January 1, 1601 (the NTFS epoch) and January 1, 1970 (the Unix epoch).
+        const NTFS_EPOCH_OFFSET: i64 = 11_644_473_600;
+
+        let created = crate::spec::extra_field::parse_ntfs_creation_time(self.extra.as_ref()?)?;
+        let secs = (created / 10_000_000) as i64 - NTFS_EPOCH_OFFSET;
+        let nanos = (created % 10_000_000) as u32 * 100;
+
+        Utc.timestamp_opt(secs, nanos).single()
+    }
+
     /// Returns an optional shared reference to the extra bytes for the entry.
     pub fn extra(&self) -> Option<&Vec<u8>> {
         self.extra.as_ref()
     }
 
+    /// Returns the entry's extra field bytes exactly as stored within the archive.
+    ///
+    /// Unlike [`ZipEntry::extra_fields()`], these are never parsed, so they suit byte-exact re-emission or signing of
+    /// an entry's headers (including any trailing bytes which don't form a complete record).
+    pub fn raw_extra(&self) -> Option<&[u8]> {
+        self.extra.as_deref()
+    }
+
+    /// Returns the records parsed from the entry's extra field bytes.
+    ///
+    /// Any trailing bytes which don't form a complete record are ignored. See [`ZipEntry::raw_extra()`] for the
+    /// unparsed bytes.
+    pub fn extra_fields(&self) -> Vec<ExtraField<'_>> {
+        match &self.extra {
+            Some(extra) => crate::spec::extra_field::parse_extra_fields(extra),
+            None => Vec::new(),
+        }
+    }
+
     /// Returns a shared reference to the compression type of the entry.
     pub fn compression(&self) -> &Compression {
         &self.compression
     }
+
+    /// Returns the compression type of the entry alongside its method identifier as stored within the archive.
+    ///
+    /// Entries with an unsupported compression method are rejected when the archive is opened, so the identifier is
+    /// always that of a known method.
+    pub fn compression_info(&self) -> (Compression, u16) {
+        (self.compression, self.compression.to_u16())
+    }
+
+    /// Returns the Deflate compression option recorded for the entry, or `None` if it isn't Deflate compressed.
+    pub fn deflate_option(&self) -> Option<DeflateOption> {
+        match self.compression {
+            // https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#444
+            #[cfg(feature = "deflate")]
+            Compression::Deflate => match self.flags.compression_options {
+                0 => Some(DeflateOption::Normal),
+                1 => Some(DeflateOption::Maximum),
+                2 => Some(DeflateOption::Fast),
+                _ => Some(DeflateOption::SuperFast),
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the offset of the entry's local file header from the start of the archive.
+    ///
+    /// This is only available for entries read from the central directory.
+    pub fn local_header_offset(&self) -> Option<u64> {
+        self.offset
+    }
+
+    /// Returns the minimum version of the specification needed to extract the entry.
+    ///
+    /// The value is stored as the major version multiplied by ten plus the minor version (eg. 45 for 4.5).
+    pub fn version_needed(&self) -> u16 {
+        self.version_needed
+    }
+
+    /// Returns the host system which produced the entry, if known.
+    ///
+    /// This is only available for entries read from the central directory.
+    pub fn host_system(&self) -> Option<HostSystem> {
+        self.version_made_by.map(|version| HostSystem::from_u8((version >> 8) as u8))
+    }
+
+    /// Returns the entry's Unix permission bits if they were recorded by the archiver (eg. 0o644).
+    ///
+    /// These are only available for entries read from the central directory which were written on a Unix-like host.
+    pub fn unix_permissions(&self) -> Option<u32> {
+        self.unix_mode().map(|mode| mode & 0o7777)
+    }
+
+    /// Returns whether or not the entry is a symbolic link, in which case its data is the link's target path.
+    pub fn is_symlink(&self) -> bool {
+        matches!(self.unix_mode(), Some(mode) if mode & 0o170000 == 0o120000)
+    }
+
+    /// Returns whether or not the archiver flagged the entry's data as apparently being text.
+    ///
+    /// This is only available for entries read from the central directory, and is merely a hint as archivers commonly
+    /// guess it from the data (or never set it at all).
+    pub fn is_text(&self) -> bool {
+        // https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#4414
+        matches!(self.internal_attributes, Some(attributes) if attributes & 0x1 != 0)
+    }
+
+    // Returns the entry's full Unix mode (including the file type bits) if it was recorded by the archiver.
+    fn unix_mode(&self) -> Option<u32> {
+        // Hosts 3 (UNIX) and 19 (OS X) both store a Unix mode within the upper bytes of the external attributes.
+        match (self.version_made_by.map(|version| version >> 8), self.external_attributes) {
+            (Some(3 | 19), Some(attributes)) if attributes >> 16 != 0 => Some(attributes >> 16),
+            _ => None,
+        }
+    }
+
+    // Returns an error if the entry's version needed to extract implies a feature which we don't support.
+    pub(crate) fn assert_supported(&self) -> Result<()> {
+        if self.flags.encrypted {
+            return Err(ZipError::FeatureNotSupported("Reading encrypted entries without a password"));
+        }
+
+        self.assert_version_supported()
+    }
+
+    pub(crate) fn assert_version_supported(&self) -> Result<()> {
+        match crate::spec::version::as_unsupported_feature(self.version_needed) {
+            Some(feature) => Err(ZipError::FeatureNotSupported(feature)),
+            None => Ok(()),
+        }
+    }
+
+    // Returns the number of heap-allocated bytes retained by this entry's variable-length fields.
+    pub(crate) fn heap_usage(&self) -> usize {
+        let comment = self.comment.as_ref().map(String::capacity).unwrap_or_default();
+        let extra = self.extra.as_ref().map(Vec::capacity).unwrap_or_default();
+
+        self.name.capacity() + comment + extra
+    }
 }
 
 pub(crate) enum PrependReader<'a, R: AsyncRead + Unpin> {
     Normal(OwnedReader<'a, R>),
-    Prepend(OwnedReader<'a, AsyncPrependReader<R>>),
+    Prepend(OwnedReader<'a, PositionedReader<R>>),
+}
+
+// A prepend reader which tracks its position within a non-seekable source, so that the offset of each header can be
+// reported when it fails to parse. Bytes which are prepended back onto the reader are deducted from its position.
+pub(crate) struct PositionedReader<R: AsyncRead + Unpin> {
+    inner: AsyncPrependReader<R>,
+    position: u64,
+}
+
+impl<R: AsyncRead + Unpin> PositionedReader<R> {
+    pub(crate) fn new(inner: R) -> Self {
+        Self { inner: AsyncPrependReader::new(inner), position: 0 }
+    }
+
+    pub(crate) fn position(&self) -> u64 {
+        self.position
+    }
+
+    pub(crate) fn prepend(&mut self, data: &[u8]) {
+        self.inner.prepend(data);
+        self.position -= data.len() as u64;
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for PositionedReader<R> {
+    fn poll_read(mut self: Pin<&mut Self>, c: &mut Context<'_>, b: &mut ReadBuf<'_>) -> Poll<tokio::io::Result<()>> {
+        let prev_len = b.filled().len();
+        let poll = Pin::new(&mut self.inner).poll_read(c, b);
+
+        if let Poll::Ready(Ok(())) = poll {
+            self.position += (b.filled().len() - prev_len) as u64;
+        }
+
+        poll
+    }
 }
 
 impl<'a, R: AsyncRead + Unpin> AsyncRead for PrependReader<'a, R> {
@@ -126,6 +688,8 @@ impl<'a, R: AsyncRead + Unpin> AsyncRead for OwnedReader<'a, R> {
 pub(crate) enum LocalReader<'a, R: AsyncRead + Unpin> {
     Standard(CompressionReader<PrependReader<'a, R>>),
     Stream(CompressionReader<AsyncDelimiterReader<PrependReader<'a, R>>>),
+    Boundary(CompressionReader<BoundaryReader<PrependReader<'a, R>>>),
+    Encrypted(CompressionReader<ZipCryptoReader<PrependReader<'a, R>>>),
 }
 
 impl<'a, R: AsyncRead + Unpin> AsyncRead for LocalReader<'a, R> {
@@ -133,24 +697,48 @@ impl<'a, R: AsyncRead + Unpin> AsyncRead for LocalReader<'a, R> {
         match *self {
             LocalReader::Standard(ref mut inner) => Pin::new(inner).poll_read(c, b),
             LocalReader::Stream(ref mut inner) => Pin::new(inner).poll_read(c, b),
+            LocalReader::Boundary(ref mut inner) => Pin::new(inner).poll_read(c, b),
+            LocalReader::Encrypted(ref mut inner) => Pin::new(inner).poll_read(c, b),
         }
     }
 }
 
+impl<'a, R: AsyncRead + Unpin> LocalReader<'a, R> {
+    fn poll_read_vectored(
+        mut self: Pin<&mut Self>,
+        c: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<tokio::io::Result<usize>> {
+        match *self {
+            LocalReader::Standard(ref mut inner) => Pin::new(inner).poll_read_vectored(c, bufs),
+            LocalReader::Stream(ref mut inner) => Pin::new(inner).poll_read_vectored(c, bufs),
+            LocalReader::Boundary(ref mut inner) => Pin::new(inner).poll_read_vectored(c, bufs),
+            LocalReader::Encrypted(ref mut inner) => Pin::new(inner).poll_read_vectored(c, bufs),
+        }
+    }
+}
+
+/// The default maximum number of decompressed bytes which an entry reader will buffer in memory (1 GiB).
+pub const DEFAULT_BUFFER_CAP: u64 = 1024 * 1024 * 1024;
+
+// The maximum number of bytes preallocated when buffering an entry, regardless of its recorded size.
+pub(crate) const MAX_PREALLOCATION: u64 = 64 * 1024;
+
 /// A ZIP file entry reader which may implement decompression.
 pub struct ZipEntryReader<'a, R: AsyncRead + Unpin> {
     pub(crate) entry: &'a ZipEntry,
     pub(crate) reader: LocalReader<'a, R>,
     pub(crate) hasher: Hasher,
     pub(crate) consumed: bool,
-    pub(crate) data_descriptor: Option<(u32, u32, u32)>,
+    pub(crate) data_descriptor: Option<(u32, u64, u64)>,
+    pub(crate) buffer_cap: u64,
+    pub(crate) bytes_read: u64,
 }
 
 impl<'a, R: AsyncRead + Unpin> ZipEntryReader<'a, R> {
     /// Construct an entry reader from its raw parts (a shared reference to the entry and an inner reader).
     pub(crate) fn from_raw(entry: &'a ZipEntry, reader: CompressionReader<PrependReader<'a, R>>, _: bool) -> Self {
-        let reader = LocalReader::Standard(reader);
-        ZipEntryReader { entry, reader, hasher: Hasher::new(), consumed: false, data_descriptor: None }
+        ZipEntryReader::from_parts(entry, LocalReader::Standard(reader))
     }
 
     /// Construct an entry reader from its raw parts (a shared reference to the entry and an inner reader).
@@ -159,8 +747,30 @@ impl<'a, R: AsyncRead + Unpin> ZipEntryReader<'a, R> {
         reader: CompressionReader<AsyncDelimiterReader<PrependReader<'a, R>>>,
         _: bool,
     ) -> Self {
-        let reader = LocalReader::Stream(reader);
-        ZipEntryReader { entry, reader, hasher: Hasher::new(), consumed: false, data_descriptor: None }
+        ZipEntryReader::from_parts(entry, LocalReader::Stream(reader))
+    }
+
+    /// Construct an entry reader from its raw parts, where the end of the entry's data is found by scanning for the
+    /// record which follows its data descriptor (as the descriptor's own signature is optional).
+    pub(crate) fn with_boundary(
+        entry: &'a ZipEntry,
+        reader: CompressionReader<BoundaryReader<PrependReader<'a, R>>>,
+    ) -> Self {
+        ZipEntryReader::from_parts(entry, LocalReader::Boundary(reader))
+    }
+
+    /// Construct an entry reader from its raw parts, where the entry's data is decrypted before it's decompressed.
+    pub(crate) fn from_encrypted(
+        entry: &'a ZipEntry,
+        reader: CompressionReader<ZipCryptoReader<PrependReader<'a, R>>>,
+    ) -> Self {
+        ZipEntryReader::from_parts(entry, LocalReader::Encrypted(reader))
+    }
+
+    fn from_parts(entry: &'a ZipEntry, reader: LocalReader<'a, R>) -> Self {
+        let buffer_cap = DEFAULT_BUFFER_CAP;
+        let hasher = Hasher::new();
+        ZipEntryReader { entry, reader, hasher, consumed: false, data_descriptor: None, buffer_cap, bytes_read: 0 }
     }
 
     /// Returns a reference to the inner entry's data.
@@ -168,20 +778,60 @@ impl<'a, R: AsyncRead + Unpin> ZipEntryReader<'a, R> {
         self.entry
     }
 
+    /// Sets the maximum number of decompressed bytes which [`ZipEntryReader::read_to_end_crc()`] and
+    /// [`ZipEntryReader::read_to_string_crc()`] will buffer in memory (defaulting to [`DEFAULT_BUFFER_CAP`]).
+    ///
+    /// Either returns [`ZipError::EntryTooLargeToBuffer`] for a larger entry, which should instead be streamed via
+    /// [`ZipEntryReader::copy_to_end_crc()`], as it only ever holds a single buffer's worth of data in memory.
+    pub fn buffer_cap(mut self, buffer_cap: u64) -> Self {
+        self.buffer_cap = buffer_cap;
+        self
+    }
+
+    // Returns the capacity to preallocate for buffering the entry, or an error if its size is known to exceed the cap.
+    //
+    // The recorded size is untrusted, so at most a small amount is preallocated and the buffer grows with the bytes
+    // actually read, which are separately checked against the cap.
+    fn buffer_capacity(&self) -> Result<usize> {
+        match self.entry.uncompressed_size {
+            Some(size) if size > self.buffer_cap => Err(ZipError::EntryTooLargeToBuffer(self.buffer_cap)),
+            size => Ok(std::cmp::min(size.unwrap_or_default(), MAX_PREALLOCATION) as usize),
+        }
+    }
+
     ///  Returns whether or not this reader has been fully consumed.
     pub fn consumed(&self) -> bool {
         self.consumed
     }
 
     /// Returns true if the computed CRC32 value of all bytes read so far matches the expected value.
+    ///
+    /// The expected value is taken from the entry's headers where known (ie. always when read from the central
+    /// directory), and otherwise from the entry's data descriptor. The zero CRC32 value stored within the local file
+    /// header of an entry with a data descriptor is never used.
     pub fn compare_crc(&mut self) -> bool {
         let hasher = std::mem::take(&mut self.hasher);
         let final_crc = hasher.finalize();
 
-        if self.entry().data_descriptor() {
-            self.data_descriptor.unwrap().0 == final_crc
+        match (self.entry().crc32(), self.data_descriptor) {
+            (Some(expected), _) | (None, Some((expected, _, _))) => expected == final_crc,
+            (None, None) => false,
+        }
+    }
+
+    // Check the computed CRC32 value against the expected value after the entry has been fully read, additionally
+    // ensuring that the central directory and data descriptor agree where both are present.
+    pub(crate) fn check_crc(&mut self) -> Result<()> {
+        if let (Some(central), Some((descriptor, _, _))) = (self.entry().crc32(), self.data_descriptor) {
+            if central != descriptor {
+                return Err(ZipError::DataDescriptorCRC32Mismatch(central, descriptor));
+            }
+        }
+
+        if self.compare_crc() {
+            Ok(())
         } else {
-            self.entry().crc32().unwrap() == final_crc
+            Err(ZipError::CRC32CheckError)
         }
     }
 
@@ -194,7 +844,7 @@ impl<'a, R: AsyncRead + Unpin> ZipEntryReader<'a, R> {
             let compressed = inner_mut.read_u32_le().await?;
             let uncompressed = inner_mut.read_u32_le().await?;
 
-            self.data_descriptor = Some((crc, compressed, uncompressed));
+            self.data_descriptor = Some((crc, compressed as u64, uncompressed as u64));
 
             let mut buffer = Vec::new();
             buffer.extend_from_slice(inner_mut.buffer());
@@ -207,39 +857,62 @@ impl<'a, R: AsyncRead + Unpin> ZipEntryReader<'a, R> {
             }
         }
 
+        if let LocalReader::Boundary(ref mut inner) = self.reader {
+            let inner_mut = inner.get_mut();
+
+            // The decompressor may have stopped short of the descriptor, so drive the scan until it's found.
+            tokio::io::copy(inner_mut, &mut tokio::io::sink()).await?;
+            self.data_descriptor = inner_mut.descriptor();
+
+            let buffer = inner_mut.take_trailing();
+
+            if let PrependReader::Prepend(inner) = inner_mut.get_mut() {
+                match inner {
+                    OwnedReader::Owned(inner) => inner.prepend(&buffer),
+                    OwnedReader::Borrow(inner) => inner.prepend(&buffer),
+                };
+            }
+
+            // The boundary was only checked against the descriptor's compressed size, so its uncompressed size is
+            // checked now that the data has been decompressed.
+            if let Some((_, _, uncompressed)) = self.data_descriptor {
+                if uncompressed != self.bytes_read {
+                    return Err(ZipError::InvalidArchive("the data descriptor's uncompressed size doesn't match"));
+                }
+            }
+        }
+
         Ok(())
     }
 
     /// A convenience method similar to `AsyncReadExt::read_to_end()` but with the final CRC32 check integrated.
     ///
-    /// Reads all bytes until EOF and returns an owned vector of them.
+    /// Reads all bytes until EOF and returns an owned vector of them. An error is returned if the entry exceeds the
+    /// reader's buffer cap (see [`ZipEntryReader::buffer_cap()`]).
     pub async fn read_to_end_crc(mut self) -> Result<Vec<u8>> {
-        let mut buffer = Vec::with_capacity(self.entry.uncompressed_size.unwrap().try_into().unwrap());
-        self.read_to_end(&mut buffer).await?;
+        let mut buffer = Vec::with_capacity(self.buffer_capacity()?);
+        let buffer_cap = self.buffer_cap;
+        (&mut self).take(buffer_cap.saturating_add(1)).read_to_end(&mut buffer).await?;
+
+        // The entry's size may be unknown (or incorrect), so the cap is also enforced on the bytes actually read.
+        if buffer.len() as u64 > buffer_cap {
+            return Err(ZipError::EntryTooLargeToBuffer(buffer_cap));
+        }
 
         self.reset_reader().await?;
+        self.check_crc()?;
 
-        if self.compare_crc() {
-            Ok(buffer)
-        } else {
-            Err(ZipError::CRC32CheckError)
-        }
+        Ok(buffer)
     }
 
     /// A convenience method similar to `AsyncReadExt::read_to_string()` but with the final CRC32 check integrated.
     ///
-    /// Reads all bytes until EOF and returns an owned string of them.
-    pub async fn read_to_string_crc(mut self) -> Result<String> {
-        let mut buffer = String::with_capacity(self.entry.uncompressed_size.unwrap().try_into().unwrap());
-        self.read_to_string(&mut buffer).await?;
-
-        self.reset_reader().await?;
-
-        if self.compare_crc() {
-            Ok(buffer)
-        } else {
-            Err(ZipError::CRC32CheckError)
-        }
+    /// Reads all bytes until EOF and returns an owned string of them. An error is returned if the entry exceeds the
+    /// reader's buffer cap (see [`ZipEntryReader::buffer_cap()`]).
+    pub async fn read_to_string_crc(self) -> Result<String> {
+        // Decoding only once all bytes are read ensures a character split by the cap isn't reported as invalid UTF-8.
+        let buffer = self.read_to_end_crc().await?;
+        String::from_utf8(buffer).map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err).into())
     }
 
     /// A convenience method for buffered copying of bytes to a writer with the final CRC32 check integrated.
@@ -248,20 +921,97 @@ impl<'a, R: AsyncRead + Unpin> ZipEntryReader<'a, R> {
     /// Any bytes written to the writer cannot be unwound, thus the caller should appropriately handle the side effects
     /// of a failed CRC32 check.
     ///
+    /// Unlike [`ZipEntryReader::read_to_end_crc()`], no more than a single buffer's worth of data is held in memory at
+    /// once, so this suits entries of any size (including those beyond the reader's buffer cap).
+    ///
     /// Prefer this method over tokio::io::copy as we have the ability to specify the buffer size (64kb recommended on
     /// modern systems), whereas, tokio's default implementation uses 2kb, so many more calls to read() have to take
     /// place.
     pub async fn copy_to_end_crc<W: AsyncWrite + Unpin>(mut self, writer: &mut W, buffer: usize) -> Result<()> {
         let mut reader = BufReader::with_capacity(buffer, &mut self);
-        tokio::io::copy_buf(&mut reader, writer).await.unwrap();
+        tokio::io::copy_buf(&mut reader, writer).await?;
 
         self.reset_reader().await?;
+        self.check_crc()
+    }
 
-        if self.compare_crc() {
-            Ok(())
-        } else {
-            Err(ZipError::CRC32CheckError)
+    /// Reads up to `max` decompressed bytes as an owned chunk, or returns `None` once the entry has been fully read.
+    ///
+    /// This suits forwarding an entry through a bounded channel, where awaiting each send naturally applies
+    /// backpressure. Once the end of the entry is reached, the final CRC32 check is performed and any mismatch is
+    /// returned as an error in place of `None`. An empty chunk is returned if `max` is zero.
+    pub async fn read_chunk(&mut self, max: usize) -> Result<Option<Bytes>> {
+        if self.consumed {
+            return Ok(None);
+        } else if max == 0 {
+            return Ok(Some(Bytes::new()));
+        }
+
+        let mut buffer = vec![0; max];
+        let read = self.read(&mut buffer).await?;
+
+        if read == 0 {
+            self.reset_reader().await?;
+            self.check_crc()?;
+            return Ok(None);
         }
+
+        buffer.truncate(read);
+        Ok(Some(Bytes::from(buffer)))
+    }
+
+    /// Consumes this entry reader and returns an adapter which yields at most `limit` decompressed bytes.
+    ///
+    /// # Note
+    /// No CRC32 check is performed by the adapter as the entry is unlikely to be fully read. Once the adapter is
+    /// dropped, any borrow of the parent ZIP file reader is released and other entries may be opened.
+    pub fn limit(self, limit: u64) -> LimitedEntryReader<'a, R> {
+        LimitedEntryReader { inner: self.take(limit) }
+    }
+}
+
+impl<'a, R: AsyncRead + AsyncSeek + Unpin> ZipEntryReader<'a, R> {
+    /// Seeks to an offset within a stored entry's data, returning the new offset from the start of the entry.
+    ///
+    /// The offset is clamped to the bounds of the entry, so seeking before its start or past its end positions the
+    /// reader at the start or end respectively. A [`ZipError::NotSeekable`] error is returned for compressed entries,
+    /// entries read via the stream reader, and entries whose size is deferred to a data descriptor.
+    ///
+    /// # Note
+    /// The CRC32 value computed over the bytes read so far is reset by each seek, so a subsequent CRC32 check will only
+    /// succeed if the entry is then read in its entirety from the start.
+    pub async fn seek_stored(&mut self, position: SeekFrom) -> Result<u64> {
+        let size = match (self.entry.compression, self.entry.compressed_size) {
+            (Compression::Stored, Some(size)) => size,
+            _ => return Err(ZipError::NotSeekable),
+        };
+
+        let inner = match &mut self.reader {
+            LocalReader::Standard(CompressionReader::Stored(inner)) => inner,
+            _ => return Err(ZipError::NotSeekable),
+        };
+
+        let current = size - inner.limit();
+        let target = match position {
+            SeekFrom::Start(offset) => offset.min(size) as i128,
+            SeekFrom::Current(offset) => current as i128 + offset as i128,
+            SeekFrom::End(offset) => size as i128 + offset as i128,
+        };
+        let target = target.clamp(0, size as i128) as u64;
+
+        let source = match inner.get_mut() {
+            PrependReader::Normal(OwnedReader::Owned(source)) => source,
+            PrependReader::Normal(OwnedReader::Borrow(source)) => &mut **source,
+            PrependReader::Prepend(_) => return Err(ZipError::NotSeekable),
+        };
+
+        source.seek(SeekFrom::Current(target as i64 - current as i64)).await?;
+        inner.set_limit(size - target);
+
+        self.hasher = Hasher::new();
+        self.consumed = false;
+
+        Ok(target)
     }
 }
 
@@ -280,10 +1030,81 @@ impl<'a, R: AsyncRead + Unpin> AsyncRead for ZipEntryReader<'a, R> {
         }
 
         self.hasher.update(&b.filled()[prev_len..b.filled().len()]);
+        self.bytes_read += (b.filled().len() - prev_len) as u64;
         poll
     }
 }
 
+impl<'a, R: AsyncRead + Unpin> ZipEntryReader<'a, R> {
+    /// Attempts to read decompressed bytes into a sequence of buffers, filling each in order, and returns the total
+    /// bytes read.
+    ///
+    /// tokio's `AsyncRead` has no vectored counterpart to `poll_read()`, so this is provided alongside the impl instead
+    /// and may be driven via [`std::future::poll_fn()`]. As with `poll_read()`, zero bytes indicates that the entry
+    /// has been fully read.
+    ///
+    /// # Note
+    /// Only stored entries are read into more than one buffer per call, with a buffer only moved onto once the
+    /// previous has been completely filled. All other entries fall back to reading into the first non-empty buffer
+    /// alone, as their data is decompressed through an internal buffer regardless.
+    pub fn poll_read_vectored(
+        mut self: Pin<&mut Self>,
+        c: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<tokio::io::Result<usize>> {
+        let read = std::task::ready!(Pin::new(&mut self.reader).poll_read_vectored(c, bufs))?;
+
+        if read == 0 && bufs.iter().any(|buf| !buf.is_empty()) {
+            self.consumed = true;
+        }
+
+        let mut remaining = read;
+        for buf in bufs.iter() {
+            let filled = remaining.min(buf.len());
+            self.hasher.update(&buf[..filled]);
+            remaining -= filled;
+        }
+
+        self.bytes_read += read as u64;
+        Poll::Ready(Ok(read))
+    }
+}
+
+/// An entry reader adapter which yields at most a fixed number of decompressed bytes.
+///
+/// # Note
+/// This adapter cannot be manually constructed; instead, use [`ZipEntryReader::limit()`].
+pub struct LimitedEntryReader<'a, R: AsyncRead + Unpin> {
+    inner: Take<ZipEntryReader<'a, R>>,
+}
+
+impl<'a, R: AsyncRead + Unpin> LimitedEntryReader<'a, R> {
+    /// Returns a reference to the inner entry's data.
+    pub fn entry(&self) -> &ZipEntry {
+        self.inner.get_ref().entry()
+    }
+
+    /// Returns the number of bytes which may still be read before the limit is reached.
+    pub fn remaining(&self) -> u64 {
+        self.inner.limit()
+    }
+
+    /// Consumes this adapter and returns the inner entry reader.
+    pub fn into_inner(self) -> ZipEntryReader<'a, R> {
+        self.inner.into_inner()
+    }
+}
+
+impl<'a, R: AsyncRead + Unpin> AsyncRead for LimitedEntryReader<'a, R> {
+    fn poll_read(mut self: Pin<&mut Self>, c: &mut Context<'_>, b: &mut ReadBuf<'_>) -> Poll<tokio::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_read(c, b)
+    }
+}
+
+// The source of a Deflate decoder, which replays the bytes read to detect a zlib header before the rest of the data.
+#[cfg(feature = "deflate")]
+type DeflateSource<R> = BufReader<tokio::io::Chain<std::io::Cursor<Vec<u8>>, Take<R>>>;
+
 /// A reader which may implement decompression over its inner type, and of which supports owned inner types or mutable
 /// borrows of them. Implements identical compression types to that of the crate::spec::compression::Compression enum.
 ///
@@ -291,7 +1112,13 @@ impl<'a, R: AsyncRead + Unpin> AsyncRead for ZipEntryReader<'a, R> {
 pub(crate) enum CompressionReader<R: AsyncRead + Unpin> {
     Stored(Take<R>),
     #[cfg(feature = "deflate")]
-    Deflate(bufread::DeflateDecoder<BufReader<Take<R>>>),
+    Deflate(bufread::DeflateDecoder<DeflateSource<R>>),
+    // Deflate data of which too few bytes have been read to know whether it's wrapped in a zlib stream, alongside
+    // those bytes (which are later replayed to the chosen decoder).
+    #[cfg(feature = "deflate")]
+    DeflateUndetected(Option<Take<R>>, Vec<u8>),
+    #[cfg(feature = "deflate")]
+    Zlib(bufread::ZlibDecoder<DeflateSource<R>>),
     #[cfg(feature = "bzip2")]
     Bz(bufread::BzDecoder<BufReader<Take<R>>>),
     #[cfg(feature = "lzma")]
@@ -300,6 +1127,8 @@ pub(crate) enum CompressionReader<R: AsyncRead + Unpin> {
     Zstd(bufread::ZstdDecoder<BufReader<Take<R>>>),
     #[cfg(feature = "xz")]
     Xz(bufread::XzDecoder<BufReader<Take<R>>>),
+    #[cfg(feature = "legacy")]
+    Legacy(legacy::LegacyDecoder<Take<R>>),
 }
 
 impl<R: AsyncRead + Unpin> CompressionReader<R> {
@@ -307,7 +1136,11 @@ impl<R: AsyncRead + Unpin> CompressionReader<R> {
         match self {
             CompressionReader::Stored(inner) => inner.get_mut(),
             #[cfg(feature = "deflate")]
-            CompressionReader::Deflate(inner) => inner.get_mut().get_mut().get_mut(),
+            CompressionReader::Deflate(inner) => inner.get_mut().get_mut().get_mut().1.get_mut(),
+            #[cfg(feature = "deflate")]
+            CompressionReader::DeflateUndetected(inner, _) => inner.as_mut().unwrap().get_mut(),
+            #[cfg(feature = "deflate")]
+            CompressionReader::Zlib(inner) => inner.get_mut().get_mut().get_mut().1.get_mut(),
             #[cfg(feature = "bzip2")]
             CompressionReader::Bz(inner) => inner.get_mut().get_mut().get_mut(),
             #[cfg(feature = "lzma")]
@@ -316,16 +1149,86 @@ impl<R: AsyncRead + Unpin> CompressionReader<R> {
             CompressionReader::Zstd(inner) => inner.get_mut().get_mut().get_mut(),
             #[cfg(feature = "xz")]
             CompressionReader::Xz(inner) => inner.get_mut().get_mut().get_mut(),
+            #[cfg(feature = "legacy")]
+            CompressionReader::Legacy(inner) => inner.get_mut().get_mut(),
+        }
+    }
+}
+
+impl<R: AsyncRead + Unpin> CompressionReader<R> {
+    // Reads into a sequence of buffers, filling each in order for stored data whilst the inner reader is ready, and
+    // otherwise reading into the first non-empty buffer alone.
+    fn poll_read_vectored(
+        mut self: Pin<&mut Self>,
+        c: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<tokio::io::Result<usize>> {
+        if let CompressionReader::Stored(ref mut inner) = *self {
+            let mut total = 0;
+
+            for buf in bufs.iter_mut().filter(|buf| !buf.is_empty()) {
+                let mut read_buf = ReadBuf::new(buf);
+
+                match Pin::new(&mut *inner).poll_read(c, &mut read_buf) {
+                    Poll::Ready(Ok(())) => {}
+                    Poll::Ready(Err(err)) if total == 0 => return Poll::Ready(Err(err)),
+                    Poll::Pending if total == 0 => return Poll::Pending,
+                    _ => break,
+                }
+
+                total += read_buf.filled().len();
+                if read_buf.remaining() > 0 {
+                    break;
+                }
+            }
+
+            return Poll::Ready(Ok(total));
+        }
+
+        match bufs.iter_mut().find(|buf| !buf.is_empty()) {
+            Some(buf) => {
+                let mut read_buf = ReadBuf::new(buf);
+                std::task::ready!(self.poll_read(c, &mut read_buf))?;
+                Poll::Ready(Ok(read_buf.filled().len()))
+            }
+            None => Poll::Ready(Ok(0)),
         }
     }
 }
 
 impl<R: AsyncRead + Unpin> AsyncRead for CompressionReader<R> {
     fn poll_read(mut self: Pin<&mut Self>, c: &mut Context<'_>, b: &mut ReadBuf<'_>) -> Poll<tokio::io::Result<()>> {
+        #[cfg(feature = "deflate")]
+        if let CompressionReader::DeflateUndetected(ref mut inner, ref mut prefix) = *self {
+            // A source may return fewer bytes per read than are needed to tell, so read until there are enough.
+            while prefix.len() < ZLIB_DETECTION_LENGTH {
+                let mut buffer = [0; ZLIB_DETECTION_LENGTH];
+                let mut read_buf = ReadBuf::new(&mut buffer[prefix.len()..]);
+                std::task::ready!(Pin::new(inner.as_mut().unwrap()).poll_read(c, &mut read_buf))?;
+
+                if read_buf.filled().is_empty() {
+                    break;
+                }
+                prefix.extend_from_slice(read_buf.filled());
+            }
+
+            let zlib = is_zlib_wrapped(prefix);
+            let reader = BufReader::new(std::io::Cursor::new(std::mem::take(prefix)).chain(inner.take().unwrap()));
+
+            *self = match zlib {
+                true => CompressionReader::Zlib(bufread::ZlibDecoder::new(reader)),
+                false => CompressionReader::Deflate(bufread::DeflateDecoder::new(reader)),
+            };
+        }
+
         match *self {
             CompressionReader::Stored(ref mut inner) => Pin::new(inner).poll_read(c, b),
             #[cfg(feature = "deflate")]
             CompressionReader::Deflate(ref mut inner) => Pin::new(inner).poll_read(c, b),
+            #[cfg(feature = "deflate")]
+            CompressionReader::DeflateUndetected(..) => unreachable!(),
+            #[cfg(feature = "deflate")]
+            CompressionReader::Zlib(ref mut inner) => Pin::new(inner).poll_read(c, b),
             #[cfg(feature = "bzip2")]
             CompressionReader::Bz(ref mut inner) => Pin::new(inner).poll_read(c, b),
             #[cfg(feature = "lzma")]
@@ -334,16 +1237,18 @@ impl<R: AsyncRead + Unpin> AsyncRead for CompressionReader<R> {
             CompressionReader::Zstd(ref mut inner) => Pin::new(inner).poll_read(c, b),
             #[cfg(feature = "xz")]
             CompressionReader::Xz(ref mut inner) => Pin::new(inner).poll_read(c, b),
+            #[cfg(feature = "legacy")]
+            CompressionReader::Legacy(ref mut inner) => Pin::new(inner).poll_read(c, b),
         }
     }
 }
 
 impl<'a, R: AsyncRead + Unpin> CompressionReader<R> {
-    pub(crate) fn from_reader(compression: &Compression, reader: Take<R>) -> Self {
-        match compression {
+    pub(crate) fn from_reader(entry: &ZipEntry, reader: Take<R>) -> Self {
+        match entry.compression() {
             Compression::Stored => CompressionReader::Stored(reader),
             #[cfg(feature = "deflate")]
-            Compression::Deflate => CompressionReader::Deflate(bufread::DeflateDecoder::new(BufReader::new(reader))),
+            Compression::Deflate => CompressionReader::DeflateUndetected(Some(reader), Vec::new()),
             #[cfg(feature = "bzip2")]
             Compression::Bz => CompressionReader::Bz(bufread::BzDecoder::new(BufReader::new(reader))),
             #[cfg(feature = "lzma")]
@@ -352,10 +1257,39 @@ impl<'a, R: AsyncRead + Unpin> CompressionReader<R> {
             Compression::Zstd => CompressionReader::Zstd(bufread::ZstdDecoder::new(BufReader::new(reader))),
             #[cfg(feature = "xz")]
             Compression::Xz => CompressionReader::Xz(bufread::XzDecoder::new(BufReader::new(reader))),
+            #[cfg(feature = "legacy")]
+            compression @ (Compression::Shrink | Compression::Reduce(_)) => {
+                CompressionReader::Legacy(legacy::LegacyDecoder::new(reader, *compression, entry.uncompressed_size))
+            }
         }
     }
 }
 
+// The number of bytes from the start of an entry's Deflate data which are needed to detect a zlib header.
+#[cfg(feature = "deflate")]
+const ZLIB_DETECTION_LENGTH: usize = 5;
+
+// Returns whether or not the start of an entry's Deflate data is instead a zlib header, which some broken archivers
+// write despite the entry declaring raw Deflate.
+//
+// Any valid zlib header begins a stored block when read as raw Deflate, so such data is only treated as zlib-wrapped
+// if it's also an invalid stored block (ie. the block's length isn't followed by its one's complement). Data shorter
+// than the five bytes needed to tell is always treated as raw Deflate.
+//
+// https://www.rfc-editor.org/rfc/rfc1950#section-2.2
+#[cfg(feature = "deflate")]
+fn is_zlib_wrapped(data: &[u8]) -> bool {
+    if data.len() < ZLIB_DETECTION_LENGTH {
+        return false;
+    }
+
+    let (cmf, flg) = (data[0], data[1]);
+    let zlib = cmf & 0x0F == 8 && cmf >> 4 <= 7 && u16::from_be_bytes([cmf, flg]) % 31 == 0 && flg & 0x20 == 0;
+    let stored_block = u16::from_le_bytes([data[1], data[2]]) == !u16::from_le_bytes([data[3], data[4]]);
+
+    zlib && !stored_block
+}
+
 macro_rules! reader_entry_impl {
     () => {
         /// Returns a shared reference to a list of the ZIP file's entries.
@@ -373,9 +1307,65 @@ macro_rules! reader_entry_impl {
             None
         }
 
+        /// Searches for an entry with a specific filename, returning [`ZipError::EntryNotFound`] if there's none.
+        ///
+        /// [`ZipError::EntryNotFound`]: crate::error::ZipError::EntryNotFound
+        pub fn try_entry(&self, name: &str) -> crate::error::Result<(usize, &ZipEntry)> {
+            self.entry(name).ok_or_else(|| crate::error::ZipError::EntryNotFound(name.to_string()))
+        }
+
+        /// Returns the entries whose filename ends with the provided extension (eg. `"png"` for `.png` files), in the
+        /// order they appear within the central directory.
+        pub fn find_by_extension(&self, ext: &str) -> Vec<(usize, &ZipEntry)> {
+            crate::read::find_by_extension(self.entries(), ext, false)
+        }
+
+        /// Returns the entries whose filename ends with the provided extension, ignoring ASCII case (eg. `"png"` also
+        /// matches `.PNG` files).
+        pub fn find_by_extension_ignore_case(&self, ext: &str) -> Vec<(usize, &ZipEntry)> {
+            crate::read::find_by_extension(self.entries(), ext, true)
+        }
+
+        /// Returns whether or not the entries' local file headers appear in the same order as the central directory.
+        ///
+        /// Archives for which this returns false can still be read in any order, but sequential extraction will
+        /// require seeking backwards through the source.
+        pub fn is_physically_ordered(&self) -> bool {
+            self.entries().windows(2).all(|pair| pair[0].offset <= pair[1].offset)
+        }
+
+        /// Checks the archive's structure using only its parsed metadata, returning a list of any issues found.
+        ///
+        /// This checks that each entry's local file header (and compressed data) lies before the central directory,
+        /// that no two entries grossly overlap, and that the central directory's recorded size and position are
+        /// consistent with the end of central directory record. No entry data is read, so this is cheap enough to run
+        /// before any extraction.
+        pub fn validate_structure(&self) -> std::result::Result<(), Vec<crate::read::StructureWarning>> {
+            crate::read::validate_structure(&self.entries, &self.layout)
+        }
+
         /// Returns an optional ending comment.
+        ///
+        /// This is always `None` if comment decoding was skipped via the reader's options.
         pub fn comment(&self) -> Option<&str> {
-            self.comment.as_ref().map(|x| &x[..])
+            self.comment.as_ref().and_then(|comment| comment.as_str())
+        }
+
+        /// Returns the raw bytes of an optional ending comment, regardless of whether or not it was decoded.
+        pub fn comment_bytes(&self) -> Option<&[u8]> {
+            self.comment.as_ref().map(|comment| comment.as_bytes())
+        }
+
+        /// Returns an approximation of the number of heap-allocated bytes retained by this reader's metadata.
+        ///
+        /// This accounts for the list of entries, each entry's name, comment, and extra field bytes, and the ending
+        /// comment. It doesn't account for any memory held by the underlying source.
+        pub fn memory_usage(&self) -> usize {
+            let entries = self.entries.capacity() * std::mem::size_of::<crate::read::ZipEntry>();
+            let entries_heap: usize = self.entries.iter().map(|entry| entry.heap_usage()).sum();
+            let comment = self.comment.as_ref().map(|comment| comment.capacity()).unwrap_or_default();
+
+            entries + entries_heap + comment
         }
     };
 }
@@ -26,27 +26,115 @@
 //! ```
 
 use crate::error::{Result, ZipError};
-use crate::read::{CompressionReader, OwnedReader, PrependReader, ZipEntry, ZipEntryReader};
+use crate::read::zipcrypto::{ZipCryptoKeys, ZipCryptoReader, ENCRYPTION_HEADER_LENGTH};
+use crate::read::{
+    ArchiveComment, ArchiveLayout, CompressionReader, ExtractOptions, ExtractionPlan, OwnedReader, PrependReader,
+    ReaderOptions, SymlinkPolicy, ZipEntry, ZipEntryReader, DEFAULT_BUFFER_CAP, MAX_PREALLOCATION,
+};
 use crate::spec::compression::Compression;
-use crate::spec::header::{CentralDirectoryHeader, EndOfCentralDirectoryHeader, LocalFileHeader};
+use crate::spec::extra_field::{
+    parse_extra_fields, parse_info_zip_unicode, parse_zip64_extended_information, INFO_ZIP_UNICODE_COMMENT,
+    INFO_ZIP_UNICODE_PATH, ZIP64_EXTENDED_INFORMATION,
+};
+use crate::spec::header::{
+    CentralDirectoryHeader, EndOfCentralDirectoryHeader, LocalFileHeader, Zip64EndOfCentralDirectoryLocator,
+    Zip64EndOfCentralDirectoryRecord,
+};
+use crate::spec::signature::{
+    CENTRAL_DIRECTORY_FILE_HEADER, END_OF_CENTRAL_DIRECTORY, LOCAL_FILE_HEADER, ZIP64_END_OF_CENTRAL_DIRECTORY,
+    ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR,
+};
+use crate::utils::{assert_signature_at, truncated_at};
 
-use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, AsyncWrite, AsyncWriteExt};
+
+use futures_util::Stream;
 
 use async_io_utilities::AsyncDelimiterReader;
-use std::io::SeekFrom;
+use std::collections::{HashMap, HashSet};
+use std::io::{Cursor, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 /// A reader which acts over a seekable source.
 pub struct ZipFileReader<R: AsyncRead + AsyncSeek + Unpin> {
     pub(crate) reader: R,
     pub(crate) entries: Vec<ZipEntry>,
-    pub(crate) comment: Option<String>,
+    pub(crate) comment: Option<crate::read::ArchiveComment>,
+    pub(crate) layout: crate::read::ArchiveLayout,
 }
 
 impl<R: AsyncRead + AsyncSeek + Unpin> ZipFileReader<R> {
     /// Constructs a new ZIP file reader from a mutable reference to a reader.
-    pub async fn new(mut reader: R) -> Result<ZipFileReader<R>> {
-        let (entries, comment) = read_cd(&mut reader).await?;
-        Ok(ZipFileReader { reader, entries, comment })
+    pub async fn new(reader: R) -> Result<ZipFileReader<R>> {
+        ZipFileReader::with_options(reader, ReaderOptions::default()).await
+    }
+
+    /// Constructs a new ZIP file reader from a mutable reference to a reader and a set of options.
+    pub async fn with_options(mut reader: R, options: ReaderOptions) -> Result<ZipFileReader<R>> {
+        let (entries, comment, layout) = read_cd(&mut reader, &options).await?;
+        Ok(ZipFileReader { reader, entries, comment, layout })
+    }
+
+    /// Re-reads the central directory from the reader, replacing the entries and comment read previously.
+    ///
+    /// This suits a long-lived reader over an archive which may be replaced (eg. atomically rewritten) whilst open. If
+    /// the archive can no longer be read, the error is returned and the previous entries and comment are retained.
+    pub async fn reopen(&mut self) -> Result<()> {
+        self.reopen_with_options(ReaderOptions::default()).await
+    }
+
+    /// Re-reads the central directory from the reader with a set of options, replacing the entries and comment read
+    /// previously.
+    ///
+    /// See [`ZipFileReader::reopen()`] for more information.
+    pub async fn reopen_with_options(&mut self, options: ReaderOptions) -> Result<()> {
+        let (entries, comment, layout) = read_cd(&mut self.reader, &options).await?;
+
+        self.entries = entries;
+        self.comment = comment;
+        self.layout = layout;
+
+        Ok(())
+    }
+
+    /// Returns a stream of the entries within the ZIP file held by a mutable reference to a reader.
+    ///
+    /// The end of central directory record is located before this function returns, but each entry is only read from
+    /// the central directory as the stream is polled. This suits slow sources (eg. those backed by network requests)
+    /// where entries can be displayed as they arrive, rather than once the whole central directory has been read.
+    ///
+    /// The stream ends after the first error it yields. A full reader (needed to open entries for reading) can be
+    /// constructed once the stream has been consumed; wrapping the source in a
+    /// [`CachingSource`](crate::read::cache::CachingSource) avoids re-fetching the central directory to do so.
+    pub async fn entries_stream(
+        reader: &mut R,
+        options: ReaderOptions,
+    ) -> Result<impl Stream<Item = Result<ZipEntry>> + '_> {
+        let location = locate_cd(reader, &options).await?;
+        reader.seek(SeekFrom::Start(location.cent_dir_offset)).await?;
+
+        let (cent_dir_offset, size_cent_dir) = (location.cent_dir_offset, location.size_cent_dir);
+        let state = (reader, options, size_cent_dir, location.num_of_entries);
+
+        Ok(futures_util::stream::unfold(Some(state), move |state| async move {
+            let (reader, options, mut remaining, entries_left) = state?;
+            let offset = cent_dir_offset + (size_cent_dir - remaining);
+
+            let finished = match options.scan_central_directory {
+                true => remaining == 0,
+                false => entries_left == 0,
+            };
+
+            if finished {
+                return None;
+            }
+
+            match read_cd_entry(reader, &options, offset, &mut remaining).await {
+                Ok(entry) => Some((Ok(entry), Some((reader, options, remaining, entries_left.saturating_sub(1))))),
+                Err(err) => Some((Err(err), None)),
+            }
+        }))
     }
 
     crate::read::reader_entry_impl!();
@@ -54,99 +142,832 @@ impl<R: AsyncRead + AsyncSeek + Unpin> ZipFileReader<R> {
     /// Opens an entry at the provided index for reading.
     pub async fn entry_reader(&mut self, index: usize) -> Result<ZipEntryReader<'_, R>> {
         let entry = self.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
-
-        self.reader.seek(SeekFrom::Start(entry.offset.unwrap() as u64 + 4)).await?;
-
-        let header = LocalFileHeader::from_reader(&mut self.reader).await?;
-        let data_offset = (header.file_name_length + header.extra_field_length) as i64;
-        self.reader.seek(SeekFrom::Current(data_offset)).await?;
+        entry.assert_supported()?;
+        seek_to_data(&mut self.reader, entry).await?;
 
         if entry.data_descriptor() {
             let delimiter = crate::spec::signature::DATA_DESCRIPTOR.to_le_bytes();
             let reader = OwnedReader::Borrow(&mut self.reader);
             let reader = PrependReader::Normal(reader);
             let reader = AsyncDelimiterReader::new(reader, &delimiter);
-            let reader = CompressionReader::from_reader(entry.compression(), reader.take(u64::MAX));
+            let reader = CompressionReader::from_reader(entry, reader.take(u64::MAX));
 
             Ok(ZipEntryReader::with_data_descriptor(entry, reader, false))
         } else {
             let reader = OwnedReader::Borrow(&mut self.reader);
             let reader = PrependReader::Normal(reader);
-            let reader = reader.take(entry.compressed_size.unwrap().into());
-            let reader = CompressionReader::from_reader(entry.compression(), reader);
+            let reader = reader.take(entry.compressed_size.unwrap());
+            let reader = CompressionReader::from_reader(entry, reader);
 
             Ok(ZipEntryReader::from_raw(entry, reader, false))
         }
     }
+
+    /// Opens an entry at the provided index for reading, decrypting its data with the provided password.
+    ///
+    /// Only the traditional PKWARE encryption method (ie. ZipCrypto) is supported. Entries encrypted with AES use a
+    /// distinct compression method which is rejected as unsupported when the archive is opened, and entries which
+    /// aren't encrypted are read as normal (ignoring the password).
+    ///
+    /// The entry's data is decrypted before it's decompressed, with the encryption header excluded from the data
+    /// passed to the decompressor. A [`ZipError::IncorrectPassword`] error is returned if the decrypted header's check
+    /// byte doesn't match, though as this is only a single byte, an incorrect password may still occasionally pass
+    /// (in which case the CRC32 check will fail once the entry has been read).
+    pub async fn entry_reader_with_password(&mut self, index: usize, password: &[u8]) -> Result<ZipEntryReader<'_, R>> {
+        let entry = self.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
+
+        if !entry.encrypted() {
+            return self.entry_reader(index).await;
+        }
+
+        entry.assert_version_supported()?;
+        seek_to_data(&mut self.reader, entry).await?;
+
+        let mut header = [0; ENCRYPTION_HEADER_LENGTH];
+        self.reader.read_exact(&mut header).await?;
+
+        let mut keys = ZipCryptoKeys::new(password);
+        keys.decrypt(&mut header);
+
+        // Entries with a data descriptor may instead be checked against the high byte of their last modification time,
+        // as their CRC32 value wasn't known when the header was written.
+        let check = header[ENCRYPTION_HEADER_LENGTH - 1];
+        let crc_check = (entry.crc32.unwrap() >> 24) as u8;
+        let time_check = (entry.mod_time >> 8) as u8;
+
+        if check != crc_check && !(entry.data_descriptor() && check == time_check) {
+            return Err(ZipError::IncorrectPassword);
+        }
+
+        let size = entry.compressed_size.unwrap().checked_sub(ENCRYPTION_HEADER_LENGTH as u64);
+        let size = size.ok_or(ZipError::InvalidArchive("an encrypted entry is shorter than its encryption header"))?;
+
+        // The size is known from the central directory, so no delimiter is needed even if a data descriptor follows.
+        let reader = OwnedReader::Borrow(&mut self.reader);
+        let reader = PrependReader::Normal(reader);
+        let reader = ZipCryptoReader::new(reader, keys);
+        let reader = CompressionReader::from_reader(entry, reader.take(size));
+
+        Ok(ZipEntryReader::from_encrypted(entry, reader))
+    }
+
+    /// Seeks the underlying source to the start of an entry's compressed data, returning a mutable reference to the
+    /// source and the number of compressed bytes.
+    ///
+    /// This is a low-level escape hatch for callers who wish to drive decompression (or any other processing) of the
+    /// raw entry data themselves. No decompression or CRC32 checking is performed.
+    ///
+    /// # Note
+    /// The caller must not read past the returned number of bytes, else they will read into the subsequent record.
+    pub async fn seek_to_entry(&mut self, index: usize) -> Result<(&mut R, u64)> {
+        let entry = self.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
+        seek_to_data(&mut self.reader, entry).await?;
+
+        Ok((&mut self.reader, entry.compressed_size.unwrap()))
+    }
+
+    /// Opens the entry at the provided index as a ZIP file itself (ie. an archive nested within this archive).
+    ///
+    /// # Note
+    /// Reading a ZIP file requires seeking, which an entry reader can't do, so the nested archive is read fully into
+    /// memory. As with [`ZipEntryReader::read_to_end_crc()`], an entry larger than [`DEFAULT_BUFFER_CAP`] is rejected
+    /// with [`ZipError::EntryTooLargeToBuffer`]. A stored entry is read directly from the source, bypassing the
+    /// decompression pipeline.
+    pub async fn nested(&mut self, index: usize) -> Result<ZipFileReader<Cursor<Vec<u8>>>> {
+        let entry = self.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
+        entry.assert_supported()?;
+
+        let data = if *entry.compression() == Compression::Stored {
+            let crc = entry.crc32.unwrap();
+            let (reader, size) = self.seek_to_entry(index).await?;
+
+            if size > DEFAULT_BUFFER_CAP {
+                return Err(ZipError::EntryTooLargeToBuffer(DEFAULT_BUFFER_CAP));
+            }
+
+            // The recorded size is untrusted, so the buffer grows with the bytes actually read.
+            let mut data = Vec::with_capacity(std::cmp::min(size, MAX_PREALLOCATION) as usize);
+            reader.take(size).read_to_end(&mut data).await?;
+
+            if (data.len() as u64) < size {
+                return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+            }
+
+            if crc32fast::hash(&data) != crc {
+                return Err(ZipError::CRC32CheckError);
+            }
+
+            data
+        } else {
+            self.entry_reader(index).await?.read_to_end_crc().await?
+        };
+
+        ZipFileReader::new(Cursor::new(data)).await
+    }
+
+    /// Returns the number of bytes between the end of the last entry (ie. its data and any data descriptor) and the
+    /// start of the central directory.
+    ///
+    /// Writers place the central directory immediately after the last entry, so a nonzero gap may indicate alignment
+    /// padding, a signing block, or data which has been injected into the archive. The local file header of the last
+    /// entry (by offset) is read in order to determine where its data starts, and the width of any data descriptor.
+    pub async fn gap_before_central_directory(&mut self) -> Result<u64> {
+        let last = match self.entries.iter().max_by_key(|entry| entry.offset) {
+            Some(last) => last,
+            None => return Ok(self.layout.cent_dir_offset),
+        };
+
+        let offset = last.offset.unwrap();
+        self.reader.seek(SeekFrom::Start(offset)).await?;
+
+        assert_signature_at(&mut self.reader, LOCAL_FILE_HEADER, offset).await?;
+        let header =
+            LocalFileHeader::from_reader(&mut self.reader).await.map_err(truncated_at(LOCAL_FILE_HEADER, offset))?;
+        self.reader.seek(SeekFrom::Current(header.file_name_length as i64)).await?;
+        let extra = async_io_utilities::read_bytes(&mut self.reader, header.extra_field_length as usize).await?;
+        let mut end = self.reader.seek(SeekFrom::Current(0)).await? + last.compressed_size.unwrap();
+
+        if last.data_descriptor() {
+            self.reader.seek(SeekFrom::Start(end)).await?;
+
+            // The descriptor's signature is optional, and its sizes are eight bytes wide if the local file header holds
+            // a ZIP64 extended information record, regardless of whether the sizes themselves needed it.
+            let signed = self.reader.read_u32_le().await? == crate::spec::signature::DATA_DESCRIPTOR;
+            let zip64 = parse_extra_fields(&extra).iter().any(|field| field.header_id() == ZIP64_EXTENDED_INFORMATION);
+
+            end += match zip64 {
+                true => 20,
+                false => 12,
+            };
+            if signed {
+                end += 4;
+            }
+        }
+
+        self.layout
+            .cent_dir_offset
+            .checked_sub(end)
+            .ok_or(ZipError::InvalidArchive("the last entry overruns the central directory"))
+    }
+
+    /// Extracts the entry with the provided name to a destination file path, creating any missing parent directories.
+    ///
+    /// The decompressed data is checked against its CRC32 value, and if the check fails, the partially written file
+    /// is removed. The file's modification time is set to that of the entry, and on Unix, its permissions are set
+    /// where the archive recorded them. A directory entry instead creates a directory at the destination.
+    ///
+    /// # Note
+    /// Whilst the destination is chosen by the caller, the entry's name must still be a safe relative path (see
+    /// [`ZipEntry::safe_path()`]) so that an entry rejected by a full extraction is never extracted by name.
+    pub async fn extract_entry_to(&mut self, name: &str, dst: &Path) -> Result<()> {
+        let (index, entry) = self.try_entry(name)?;
+        entry.safe_path()?;
+
+        let entry_reader = self.entry_reader(index).await?;
+        extract_entry(entry_reader, dst, false).await
+    }
+
+    /// Computes what extracting all entries into a destination directory would write, using the default extraction
+    /// options.
+    pub async fn extraction_plan(&mut self, dst: &Path) -> Result<ExtractionPlan> {
+        self.extraction_plan_with_options(dst, ExtractOptions::default()).await
+    }
+
+    /// Computes what extracting all entries into a destination directory would write, using the provided extraction
+    /// options.
+    ///
+    /// The filesystem isn't touched, and the destination is assumed to start out empty. Under
+    /// [`SymlinkPolicy::SkipExternal`], each link's target is read from the source so that it can be resolved against
+    /// what the preceding entries would have created, as happens during the extraction itself.
+    pub async fn extraction_plan_with_options(
+        &mut self,
+        dst: &Path,
+        options: ExtractOptions,
+    ) -> Result<ExtractionPlan> {
+        let mut plan = ExtractionPlan::default();
+        // The safe paths (relative to the destination) of everything created so far, and of each symbolic link
+        // recreated so far alongside its resolved target.
+        let mut created = HashSet::new();
+        let mut created_links = HashMap::new();
+
+        for index in 0..self.entries.len() {
+            let entry = &self.entries[index];
+            let link = match entry.safe_path() {
+                Ok(link) => link,
+                Err(_) => {
+                    plan.rejected.push(entry.name().to_string());
+                    continue;
+                }
+            };
+            let path = dst.join(&link);
+
+            if link.ancestors().any(|ancestor| created_links.contains_key(ancestor)) {
+                plan.rejected.push(entry.name().to_string());
+                continue;
+            }
+
+            if !entry.is_symlink() || options.symlink_policy == SymlinkPolicy::AsRegularFile {
+                if entry.dir() {
+                    plan.directories.push(path);
+                } else {
+                    if let Some(mode) = entry.unix_permissions().filter(|_| !entry.is_symlink()) {
+                        plan.permissions.push((path.clone(), mode & 0o777));
+                    }
+
+                    plan.total_bytes += entry.uncompressed_size().unwrap_or_default();
+                    plan.files.push(path);
+                }
+
+                created.extend(link.ancestors().map(Path::to_path_buf));
+                continue;
+            }
+
+            if options.symlink_policy == SymlinkPolicy::RejectAll {
+                plan.rejected.push(entry.name().to_string());
+                continue;
+            }
+
+            let parent = link.parent().unwrap_or_else(|| Path::new(""));
+            created.extend(parent.ancestors().map(Path::to_path_buf));
+
+            let resolved = match options.symlink_policy {
+                SymlinkPolicy::SkipExternal => {
+                    let target = self.entry_reader(index).await?.read_to_string_crc().await?;
+
+                    match resolve_planned_link(parent, Path::new(&target), &created, &created_links) {
+                        Some(resolved) => resolved,
+                        None => {
+                            plan.skipped_symlinks.push(path);
+                            continue;
+                        }
+                    }
+                }
+                // Links are only followed whilst resolving targets under SkipExternal, so theirs needn't be read here.
+                _ => PathBuf::new(),
+            };
+
+            plan.symlinks.push(path);
+            created_links.insert(link, resolved);
+        }
+
+        Ok(plan)
+    }
+
+    /// Extracts all entries into a destination directory, using the default extraction options.
+    pub async fn extract_to(&mut self, dst: &Path) -> Result<()> {
+        self.extract_to_with_options(dst, ExtractOptions::default()).await
+    }
+
+    /// Extracts all entries into a destination directory, using the provided extraction options.
+    ///
+    /// Each entry is written to its [`ZipEntry::safe_path()`] relative to the destination, and any entry with an
+    /// unsafe name aborts the extraction. Symbolic links are handled as per the options' [`SymlinkPolicy`]. Under any
+    /// policy, an entry whose path passes through a symbolic link created earlier within the same extraction is
+    /// treated as unsafe, as it would otherwise be written wherever that link points.
+    ///
+    /// Each file's modification time is restored on all platforms. Its creation time (see [`ZipEntry::created()`]) is
+    /// also restored on Windows and macOS, but skipped elsewhere as no portable way to set it exists.
+    pub async fn extract_to_with_options(&mut self, dst: &Path, options: ExtractOptions) -> Result<()> {
+        // The safe paths (relative to the destination) of each symbolic link created so far.
+        let mut created_links = Vec::new();
+
+        for index in 0..self.entries.len() {
+            let entry = &self.entries[index];
+            let link = entry.safe_path()?;
+            let path = dst.join(&link);
+
+            if link.ancestors().any(|ancestor| created_links.iter().any(|created| created == ancestor)) {
+                return Err(ZipError::UnsafePath(entry.name().to_string()));
+            }
+
+            if !entry.is_symlink() || options.symlink_policy == SymlinkPolicy::AsRegularFile {
+                let entry_reader = self.entry_reader(index).await?;
+                extract_entry(entry_reader, &path, options.convert_text_line_endings).await?;
+                continue;
+            }
+
+            if options.symlink_policy == SymlinkPolicy::RejectAll {
+                return Err(ZipError::SymlinkRejected(entry.name().to_string()));
+            }
+
+            let entry_reader = self.entry_reader(index).await?;
+            let target = entry_reader.read_to_string_crc().await?;
+
+            let parent = path.parent().unwrap_or(dst);
+            tokio::fs::create_dir_all(parent).await?;
+
+            if options.symlink_policy == SymlinkPolicy::SkipExternal
+                && !is_internal_link(dst, parent, Path::new(&target)).await?
+            {
+                continue;
+            }
+
+            create_symlink(&target, &path).await?;
+            created_links.push(link);
+        }
+
+        Ok(())
+    }
 }
 
-pub(crate) async fn read_cd<R: AsyncRead + AsyncSeek + Unpin>(
-    reader: &mut R,
-) -> Result<(Vec<ZipEntry>, Option<String>)> {
-    const MAX_ENDING_LENGTH: u64 = u16::MAX as u64 + 22;
+// Returns whether or not a link's target (relative to the link's parent directory) resolves within the extraction
+// root. Both the root and the link's parent must already exist.
+//
+// The target is resolved against the filesystem one component at a time, so any symbolic links it passes through
+// (including those created earlier within the same extraction) are followed rather than taken at face value. Once a
+// component which doesn't exist yet is reached, the rest is appended as-is, and so must not contain a parent directory
+// component, as what it'd resolve to depends upon what a later entry creates there.
+async fn is_internal_link(root: &Path, parent: &Path, target: &Path) -> Result<bool> {
+    use std::path::Component;
 
-    let length = reader.seek(SeekFrom::End(0)).await?;
-    let seek_to = length.saturating_sub(MAX_ENDING_LENGTH);
+    let root = tokio::fs::canonicalize(root).await?;
+    let mut resolved = tokio::fs::canonicalize(parent).await?;
+    let mut missing = false;
 
-    reader.seek(SeekFrom::Start(seek_to)).await?;
+    for component in target.components() {
+        match component {
+            Component::Normal(name) => {
+                resolved.push(name);
 
-    let mut comment = None;
-    let delimiter = crate::spec::signature::END_OF_CENTRAL_DIRECTORY.to_le_bytes();
-    let mut reader = AsyncDelimiterReader::new(reader, &delimiter);
+                match tokio::fs::canonicalize(&resolved).await {
+                    Ok(canonical) if !missing => resolved = canonical,
+                    _ => missing = true,
+                }
+            }
+            Component::CurDir => {}
+            Component::ParentDir if !missing => {
+                resolved.pop();
+            }
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return Ok(false),
+        }
+    }
+
+    Ok(resolved.starts_with(&root))
+}
+
+// Resolves a link's target (relative to the link's parent directory) against what an extraction plan has created so
+// far, returning the resolved path relative to the destination, or None if it escapes.
+//
+// This mirrors is_internal_link() for a destination which starts out empty: recreated links are followed, and once a
+// component which hasn't been created is reached, the rest must not contain a parent directory component. A target
+// which leaves the destination before re-entering it is conservatively treated as escaping.
+fn resolve_planned_link(
+    parent: &Path,
+    target: &Path,
+    created: &HashSet<PathBuf>,
+    created_links: &HashMap<PathBuf, PathBuf>,
+) -> Option<PathBuf> {
+    use std::path::Component;
+
+    let mut resolved = parent.to_path_buf();
+    let mut missing = false;
+
+    for component in target.components() {
+        match component {
+            Component::Normal(name) => {
+                resolved.push(name);
+
+                if missing {
+                    continue;
+                }
+                if let Some(link_target) = created_links.get(&resolved) {
+                    resolved = link_target.clone();
+                }
+                missing = !created.contains(&resolved);
+            }
+            Component::CurDir => {}
+            Component::ParentDir if !missing => {
+                if !resolved.pop() {
+                    return None;
+                }
+            }
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+
+    Some(resolved)
+}
+
+#[cfg(unix)]
+async fn create_symlink(target: &str, path: &Path) -> Result<()> {
+    Ok(tokio::fs::symlink(target, path).await?)
+}
+
+#[cfg(not(unix))]
+async fn create_symlink(_: &str, _: &Path) -> Result<()> {
+    Err(ZipError::FeatureNotSupported("Recreating symbolic links on non-Unix platforms"))
+}
+
+// Write an entry's decompressed data to a destination path, applying its timestamps and permissions.
+//
+// The creation time (if recorded) is only applied on Windows and macOS, as other platforms (eg. Linux) lack a portable
+// way to set it, so it's silently skipped there.
+async fn extract_entry<R: AsyncRead + Unpin>(
+    entry_reader: ZipEntryReader<'_, R>,
+    dst: &Path,
+    convert_text_line_endings: bool,
+) -> Result<()> {
+    let last_modified = SystemTime::from(*entry_reader.entry().last_modified());
+    let created = entry_reader.entry().created().map(SystemTime::from);
+    // The permissions of a symbolic link written as a regular file aren't meaningful for its contents.
+    let permissions = entry_reader.entry().unix_permissions().filter(|_| !entry_reader.entry().is_symlink());
+
+    if entry_reader.entry().dir() {
+        tokio::fs::create_dir_all(dst).await?;
+        return Ok(());
+    }
+
+    if let Some(parent) = dst.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+
+    let mut file = tokio::fs::File::create(dst).await?;
+
+    let result = match convert_text_line_endings && entry_reader.entry().is_text() {
+        true => copy_converting_line_endings(entry_reader, &mut file).await,
+        false => entry_reader.copy_to_end_crc(&mut file, 65536).await,
+    };
+
+    if let Err(err) = result {
+        drop(file);
+        tokio::fs::remove_file(dst).await?;
+        return Err(err);
+    }
+
+    // Setting a std file's times and permissions blocks, so it's done off of the async runtime's worker threads.
+    let file = file.into_std().await;
+    let apply = move || -> std::io::Result<()> {
+        let times = std::fs::FileTimes::new().set_modified(last_modified);
+
+        #[cfg(any(windows, target_os = "macos"))]
+        let times = match created {
+            #[cfg(windows)]
+            Some(created) => std::os::windows::fs::FileTimesExt::set_created(times, created),
+            #[cfg(target_os = "macos")]
+            Some(created) => std::os::macos::fs::FileTimesExt::set_created(times, created),
+            None => times,
+        };
+
+        #[cfg(not(any(windows, target_os = "macos")))]
+        let _ = created;
+
+        file.set_times(times)?;
+
+        #[cfg(unix)]
+        if let Some(mode) = permissions {
+            use std::os::unix::fs::PermissionsExt;
+
+            // Never apply the setuid, setgid, or sticky bits from an untrusted archive.
+            file.set_permissions(std::fs::Permissions::from_mode(mode & 0o777))?;
+        }
+
+        #[cfg(not(unix))]
+        let _ = permissions;
+
+        Ok(())
+    };
+
+    tokio::task::spawn_blocking(apply).await.map_err(std::io::Error::from)??;
+    Ok(())
+}
+
+// The platform's default line ending, which text entries are converted to if requested.
+#[cfg(windows)]
+const LINE_ENDING: &[u8] = b"\r\n";
+#[cfg(not(windows))]
+const LINE_ENDING: &[u8] = b"\n";
+
+// Copy an entry's decompressed data to a writer, converting each LF or CRLF line ending to the platform's default.
+async fn copy_converting_line_endings<R: AsyncRead + Unpin, W: AsyncWrite + Unpin>(
+    mut entry_reader: ZipEntryReader<'_, R>,
+    writer: &mut W,
+) -> Result<()> {
+    let mut buffer = vec![0; 65536];
+    let mut converted = Vec::with_capacity(buffer.len());
+    // Whether the previous chunk ended with a CR, which may be the first half of a CRLF line ending.
+    let mut pending_cr = false;
 
     loop {
-        let mut buffer = [0; async_io_utilities::SUGGESTED_BUFFER_SIZE];
-        if reader.read(&mut buffer).await? == 0 {
+        let read = entry_reader.read(&mut buffer).await?;
+        if read == 0 {
             break;
         }
+
+        converted.clear();
+
+        for byte in &buffer[..read] {
+            match (std::mem::take(&mut pending_cr), *byte) {
+                (_, b'\n') => converted.extend_from_slice(LINE_ENDING),
+                (previous_cr, b'\r') => {
+                    if previous_cr {
+                        converted.push(b'\r');
+                    }
+                    pending_cr = true;
+                }
+                (true, byte) => converted.extend_from_slice(&[b'\r', byte]),
+                (false, byte) => converted.push(byte),
+            }
+        }
+
+        writer.write_all(&converted).await?;
     }
 
-    if !reader.matched() {
-        return Err(ZipError::UnexpectedHeaderError(0, crate::spec::signature::END_OF_CENTRAL_DIRECTORY));
+    if pending_cr {
+        writer.write_all(b"\r").await?;
+    }
+    writer.flush().await?;
+
+    entry_reader.reset_reader().await?;
+    entry_reader.check_crc()
+}
+
+// Seek a reader past an entry's local file header (and its variable-length fields) to the start of its data.
+async fn seek_to_data<R: AsyncRead + AsyncSeek + Unpin>(reader: &mut R, entry: &ZipEntry) -> Result<()> {
+    let offset = entry.offset.unwrap();
+    reader.seek(SeekFrom::Start(offset)).await?;
+
+    assert_signature_at(reader, LOCAL_FILE_HEADER, offset).await?;
+    let header = LocalFileHeader::from_reader(reader).await.map_err(truncated_at(LOCAL_FILE_HEADER, offset))?;
+    let data_offset = header.file_name_length as i64 + header.extra_field_length as i64;
+    reader.seek(SeekFrom::Current(data_offset)).await?;
+
+    Ok(())
+}
+
+// The location and extent of the central directory, as recorded within the end of central directory record.
+struct CentralDirectoryLocation {
+    eocdh_offset: u64,
+    num_of_entries: u64,
+    size_cent_dir: u64,
+    cent_dir_offset: u64,
+    comment: Option<ArchiveComment>,
+}
+
+pub(crate) async fn read_cd<R: AsyncRead + AsyncSeek + Unpin>(
+    reader: &mut R,
+    options: &ReaderOptions,
+) -> Result<(Vec<ZipEntry>, Option<ArchiveComment>, ArchiveLayout)> {
+    let location = locate_cd(reader, options).await?;
+    let CentralDirectoryLocation { eocdh_offset, num_of_entries, size_cent_dir, cent_dir_offset, comment } = location;
+
+    reader.seek(SeekFrom::Start(cent_dir_offset)).await?;
+
+    // Bound the initial allocation by the smallest possible size of each entry, as the entry count alone is untrusted.
+    let max_entries = size_cent_dir / CENTRAL_DIRECTORY_ENTRY_MIN_LENGTH;
+    let capacity = match (options.capacity_hint, options.scan_central_directory) {
+        (Some(capacity_hint), _) => capacity_hint,
+        (None, true) => max_entries as usize,
+        (None, false) => std::cmp::min(num_of_entries, max_entries) as usize,
+    };
+    let mut entries = Vec::with_capacity(capacity);
+    let mut remaining = size_cent_dir;
+
+    if options.scan_central_directory {
+        while remaining > 0 {
+            let offset = cent_dir_offset + (size_cent_dir - remaining);
+            entries.push(read_cd_entry(reader, options, offset, &mut remaining).await?);
+        }
+    } else {
+        for _ in 0..num_of_entries {
+            let offset = cent_dir_offset + (size_cent_dir - remaining);
+            entries.push(read_cd_entry(reader, options, offset, &mut remaining).await?);
+        }
     }
 
-    reader.reset();
-    let eocdh = EndOfCentralDirectoryHeader::from_reader(&mut reader).await?;
+    let cent_dir_read = size_cent_dir - remaining;
+    let layout = ArchiveLayout { eocdh_offset, cent_dir_offset, cent_dir_size: size_cent_dir, cent_dir_read };
+
+    Ok((entries, comment, layout))
+}
+
+// Locate and read the end of central directory record (and the ZIP64 record, if needed).
+async fn locate_cd<R: AsyncRead + AsyncSeek + Unpin>(
+    reader: &mut R,
+    options: &ReaderOptions,
+) -> Result<CentralDirectoryLocation> {
+    let length = reader.seek(SeekFrom::End(0)).await?;
+    let eocdh_offset = locate_eocdh(reader, length).await?;
+
+    reader.seek(SeekFrom::Start(eocdh_offset + 4)).await?;
+    let eocdh = EndOfCentralDirectoryHeader::from_reader(reader)
+        .await
+        .map_err(truncated_at(END_OF_CENTRAL_DIRECTORY, eocdh_offset))?;
 
     // Outdated feature so unlikely to ever make it into this crate.
     if eocdh.disk_num != eocdh.start_cent_dir_disk || eocdh.num_of_entries != eocdh.num_of_entries_disk {
         return Err(ZipError::FeatureNotSupported("Spanned/split files"));
     }
 
-    if eocdh.file_comm_length > 0 {
-        comment = Some(async_io_utilities::read_string(&mut reader, eocdh.file_comm_length as usize).await?);
+    let mut comment = None;
+
+    if eocdh.file_comm_length > 0 && options.skip_comment_decoding {
+        let raw = async_io_utilities::read_bytes(reader, eocdh.file_comm_length as usize).await?;
+        comment = Some(ArchiveComment::Raw(raw));
+    } else if eocdh.file_comm_length > 0 {
+        let decoded = async_io_utilities::read_string(reader, eocdh.file_comm_length as usize).await?;
+        comment = Some(ArchiveComment::Decoded(decoded));
+    }
+
+    let mut num_of_entries = eocdh.num_of_entries as u64;
+    let mut size_cent_dir = eocdh.size_cent_dir as u64;
+    let mut cent_dir_offset = eocdh.cent_dir_offset as u64;
+
+    // Each field may independently hold a sentinel value, indicating that its real value is stored within the ZIP64
+    // end of central directory record instead. Only the fields which hold a sentinel are substituted, as an archive
+    // may, for example, overflow its entry count whilst still having a central directory offset which fits in 32 bits.
+    if eocdh.num_of_entries == u16::MAX || eocdh.size_cent_dir == u32::MAX || eocdh.cent_dir_offset == u32::MAX {
+        if let Some(zip64) = read_zip64_eocdr(reader, eocdh_offset).await? {
+            if eocdh.num_of_entries == u16::MAX {
+                num_of_entries = zip64.num_of_entries;
+            }
+            if eocdh.size_cent_dir == u32::MAX {
+                size_cent_dir = zip64.size_cent_dir;
+            }
+            if eocdh.cent_dir_offset == u32::MAX {
+                cent_dir_offset = zip64.cent_dir_offset;
+            }
+        }
+    }
+
+    Ok(CentralDirectoryLocation { eocdh_offset, num_of_entries, size_cent_dir, cent_dir_offset, comment })
+}
+
+// The length of an end of central directory header (including its signature) without an ending comment.
+const END_OF_CENTRAL_DIRECTORY_LENGTH: u64 = 22;
+
+// The number of bytes read per step when scanning backwards for the end of central directory header.
+const END_OF_CENTRAL_DIRECTORY_SCAN_LENGTH: u64 = 1024;
+
+// Locate the offset of the end of central directory header by scanning backwards from the end of the source.
+//
+// The header is most commonly the final 22 bytes (ie. there's no ending comment), so only those bytes are read in the
+// first instance. Otherwise, the scan continues backwards in small steps over the region which the header and its
+// comment could occupy. This ensures that listing a large archive over a ranged source only touches its tail (and
+// later, its central directory) rather than any entry data.
+//
+// The first candidate found whose comment length fits within the remaining bytes is chosen, so a signature appearing
+// within the comment itself is skipped over.
+async fn locate_eocdh<R: AsyncRead + AsyncSeek + Unpin>(reader: &mut R, length: u64) -> Result<u64> {
+    let signature = END_OF_CENTRAL_DIRECTORY.to_le_bytes();
+    let lower_bound = length.saturating_sub(u16::MAX as u64 + END_OF_CENTRAL_DIRECTORY_LENGTH);
+    let not_found =
+        || ZipError::HeaderNotFound(crate::spec::signature::name(END_OF_CENTRAL_DIRECTORY), lower_bound, length);
+
+    let mut start = length.checked_sub(END_OF_CENTRAL_DIRECTORY_LENGTH).ok_or_else(not_found)?;
+    let mut candidate = start;
+
+    let mut buffer = vec![0; END_OF_CENTRAL_DIRECTORY_LENGTH as usize];
+    reader.seek(SeekFrom::Start(start)).await?;
+    reader.read_exact(&mut buffer).await?;
+
+    loop {
+        // The buffer always holds every byte from `start` to the end of the source.
+        for position in (start..=candidate).rev() {
+            let index = (position - start) as usize;
+
+            if buffer[index..index + 4] == signature {
+                let comment_length = u16::from_le_bytes([buffer[index + 20], buffer[index + 21]]) as u64;
+
+                if position + END_OF_CENTRAL_DIRECTORY_LENGTH + comment_length <= length {
+                    return Ok(position);
+                }
+            }
+        }
+
+        if start == lower_bound {
+            return Err(not_found());
+        }
+
+        let chunk_start = std::cmp::max(start.saturating_sub(END_OF_CENTRAL_DIRECTORY_SCAN_LENGTH), lower_bound);
+        let mut chunk = vec![0; (start - chunk_start) as usize];
+        reader.seek(SeekFrom::Start(chunk_start)).await?;
+        reader.read_exact(&mut chunk).await?;
+
+        chunk.extend_from_slice(&buffer);
+        buffer = chunk;
+        candidate = start - 1;
+        start = chunk_start;
+    }
+}
+
+// The length of a central directory file header (including its signature) with empty variable-length fields.
+const CENTRAL_DIRECTORY_ENTRY_MIN_LENGTH: u64 = 46;
+
+// Locate and read the ZIP64 end of central directory record, if present, given the offset of the standard record.
+//
+// A standard record may legitimately hold a value equal to a sentinel (eg. an archive with exactly 65535 entries), so
+// the absence of a ZIP64 locator isn't treated as an error.
+// https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#4315
+async fn read_zip64_eocdr<R: AsyncRead + AsyncSeek + Unpin>(
+    reader: &mut R,
+    eocdh_offset: u64,
+) -> Result<Option<Zip64EndOfCentralDirectoryRecord>> {
+    const LOCATOR_LENGTH: u64 = 20;
+
+    if eocdh_offset < LOCATOR_LENGTH {
+        return Ok(None);
+    }
+
+    let locator_offset = eocdh_offset - LOCATOR_LENGTH;
+    reader.seek(SeekFrom::Start(locator_offset)).await?;
+
+    if reader.read_u32_le().await? != ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR {
+        return Ok(None);
+    }
+
+    let locator = Zip64EndOfCentralDirectoryLocator::from_reader(reader)
+        .await
+        .map_err(truncated_at(ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR, locator_offset))?;
+
+    if locator.start_eocdr_disk != 0 || locator.total_disks > 1 {
+        return Err(ZipError::FeatureNotSupported("Spanned/split files"));
     }
 
-    let reader = reader.into_inner();
-    reader.seek(SeekFrom::Start(eocdh.cent_dir_offset.into())).await?;
-    let mut entries = Vec::with_capacity(eocdh.num_of_entries.into());
+    reader.seek(SeekFrom::Start(locator.eocdr_offset)).await?;
+    assert_signature_at(reader, ZIP64_END_OF_CENTRAL_DIRECTORY, locator.eocdr_offset).await?;
+    let zip64 = Zip64EndOfCentralDirectoryRecord::from_reader(reader)
+        .await
+        .map_err(truncated_at(ZIP64_END_OF_CENTRAL_DIRECTORY, locator.eocdr_offset))?;
 
-    for _ in 0..eocdh.num_of_entries {
-        entries.push(read_cd_entry(reader).await?);
+    if zip64.disk_num != zip64.start_cent_dir_disk || zip64.num_of_entries != zip64.num_of_entries_disk {
+        return Err(ZipError::FeatureNotSupported("Spanned/split files"));
     }
 
-    Ok((entries, comment))
+    Ok(Some(zip64))
 }
 
-pub(crate) async fn read_cd_entry<R: AsyncRead + Unpin>(reader: &mut R) -> Result<ZipEntry> {
-    crate::utils::assert_signature(reader, crate::spec::signature::CENTRAL_DIRECTORY_FILE_HEADER).await?;
+// Read a single central directory file header and its variable-length fields.
+//
+// The offset is that of the record's signature within the source, and is only used to report where a malformed record
+// was found. The remaining number of bytes within the central directory (as recorded within the end of central
+// directory record) is decremented by the length of the record. A record which claims to be longer than the bytes
+// remaining is rejected before its variable-length fields are read, as they would otherwise consume the start of the
+// subsequent record.
+pub(crate) async fn read_cd_entry<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    options: &ReaderOptions,
+    offset: u64,
+    remaining: &mut u64,
+) -> Result<ZipEntry> {
+    assert_signature_at(reader, CENTRAL_DIRECTORY_FILE_HEADER, offset).await?;
+
+    let header = CentralDirectoryHeader::from_reader(reader)
+        .await
+        .map_err(truncated_at(CENTRAL_DIRECTORY_FILE_HEADER, offset))?;
+    let variable_length =
+        header.file_name_length as u64 + header.extra_field_length as u64 + header.file_comment_length as u64;
 
-    let header = CentralDirectoryHeader::from_reader(reader).await?;
-    let filename = async_io_utilities::read_string(reader, header.file_name_length.into()).await?;
+    *remaining = remaining
+        .checked_sub(CENTRAL_DIRECTORY_ENTRY_MIN_LENGTH + variable_length)
+        .ok_or(ZipError::InvalidArchive("a central directory header overruns the central directory"))?;
+
+    let filename = async_io_utilities::read_bytes(reader, header.file_name_length.into()).await?;
     let extra = async_io_utilities::read_bytes(reader, header.extra_field_length.into()).await?;
-    let comment = async_io_utilities::read_string(reader, header.file_comment_length.into()).await?;
+    let comment = async_io_utilities::read_bytes(reader, header.file_comment_length.into()).await?;
+
+    // A Unicode path or comment record is preferred over the header field, so long as it isn't stale.
+    let filename = match parse_info_zip_unicode(&extra, INFO_ZIP_UNICODE_PATH, &filename) {
+        Some(filename) => filename,
+        None => options.decode_string(filename, header.flags.filename_unicode)?,
+    };
+    let comment = match parse_info_zip_unicode(&extra, INFO_ZIP_UNICODE_COMMENT, &comment) {
+        Some(comment) => comment,
+        None => options.decode_string(comment, header.flags.filename_unicode)?,
+    };
+
+    let mut uncompressed_size = header.uncompressed_size as u64;
+    let mut compressed_size = header.compressed_size as u64;
+    let mut lh_offset = header.lh_offset as u64;
+
+    let zip64_uncompressed = header.uncompressed_size == u32::MAX;
+    let zip64_compressed = header.compressed_size == u32::MAX;
+    let zip64_offset = header.lh_offset == u32::MAX;
+
+    if zip64_uncompressed || zip64_compressed || zip64_offset {
+        let zip64 = parse_zip64_extended_information(&extra, zip64_uncompressed, zip64_compressed, zip64_offset)?;
+
+        uncompressed_size = zip64.uncompressed_size.unwrap_or(uncompressed_size);
+        compressed_size = zip64.compressed_size.unwrap_or(compressed_size);
+        lh_offset = zip64.lh_offset.unwrap_or(lh_offset);
+    }
 
     let entry = ZipEntry {
         name: filename,
         comment: Some(comment),
         data_descriptor: header.flags.data_descriptor,
         crc32: Some(header.crc),
-        uncompressed_size: Some(header.uncompressed_size),
-        compressed_size: Some(header.compressed_size),
+        uncompressed_size: Some(uncompressed_size),
+        compressed_size: Some(compressed_size),
         last_modified: crate::spec::date::zip_date_to_chrono(header.mod_date, header.mod_time),
+        mod_time: header.mod_time,
         extra: Some(extra),
         compression: Compression::from_u16(header.compression)?,
-        offset: Some(header.lh_offset),
+        version_needed: header.v_needed,
+        flags: header.flags,
+        offset: Some(lh_offset),
+        version_made_by: Some(header.v_made_by),
+        internal_attributes: Some(header.inter_attr),
+        external_attributes: Some(header.exter_attr),
     };
 
     Ok(entry)
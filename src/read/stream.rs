@@ -8,25 +8,39 @@
 //! ```
 
 use crate::error::{Result, ZipError};
-use crate::read::{CompressionReader, OwnedReader, PrependReader, ZipEntry, ZipEntryReader};
+use crate::read::boundary::BoundaryReader;
+use crate::read::{
+    CompressionReader, OwnedReader, PositionedReader, PrependReader, ReaderOptions, ZipEntry, ZipEntryReader,
+};
 use crate::spec::compression::Compression;
+use crate::spec::extra_field::{parse_info_zip_unicode, parse_zip64_extended_information, INFO_ZIP_UNICODE_PATH};
 use crate::spec::header::LocalFileHeader;
+use crate::spec::signature::{CENTRAL_DIRECTORY_FILE_HEADER, LOCAL_FILE_HEADER};
+use crate::utils::truncated_at;
 
-use async_io_utilities::{AsyncDelimiterReader, AsyncPrependReader};
 use tokio::io::{AsyncRead, AsyncReadExt};
 
 /// A reader which acts over a non-seekable source.
 pub struct ZipFileReader<R: AsyncRead + Unpin> {
-    pub(crate) reader: AsyncPrependReader<R>,
+    pub(crate) reader: PositionedReader<R>,
     pub(crate) entry: Option<ZipEntry>,
     pub(crate) finished: bool,
+    pub(crate) options: ReaderOptions,
 }
 
 impl<R: AsyncRead + Unpin> ZipFileReader<R> {
     /// Constructs a new ZIP file reader from a mutable reference to a reader.
     pub fn new(reader: R) -> Self {
-        let reader = AsyncPrependReader::new(reader);
-        ZipFileReader { reader, entry: None, finished: false }
+        ZipFileReader::with_options(reader, ReaderOptions::default())
+    }
+
+    /// Constructs a new ZIP file reader from a mutable reference to a reader and a set of options.
+    ///
+    /// Options which only apply to the central directory (such as its capacity hint) have no effect, as it's never
+    /// read from a non-seekable source.
+    pub fn with_options(reader: R, options: ReaderOptions) -> Self {
+        let reader = PositionedReader::new(reader);
+        ZipFileReader { reader, entry: None, finished: false, options }
     }
 
     /// Returns whether or not `entry_reader()` will yield more entries.
@@ -37,10 +51,11 @@ impl<R: AsyncRead + Unpin> ZipFileReader<R> {
     /// Opens the next entry for reading if the central directory hasn't already been reached.
     pub async fn entry_reader(&mut self) -> Result<Option<ZipEntryReader<'_, R>>> {
         // TODO: Ensure the previous entry has been fully read.
+        let offset = self.reader.position();
 
         if self.finished {
             return Ok(None);
-        } else if let Some(inner) = read_lfh(&mut self.reader).await? {
+        } else if let Some(inner) = read_lfh(&mut self.reader, offset, &self.options).await? {
             self.entry = Some(inner);
         } else {
             self.finished = true;
@@ -48,48 +63,92 @@ impl<R: AsyncRead + Unpin> ZipFileReader<R> {
         }
 
         let entry_borrow = self.entry.as_ref().unwrap();
+        entry_borrow.assert_supported()?;
 
         if entry_borrow.data_descriptor() {
-            let delimiter = crate::spec::signature::DATA_DESCRIPTOR.to_le_bytes();
+            // The data descriptor's signature is optional, so the following record's signature is scanned for instead.
             let reader = OwnedReader::Borrow(&mut self.reader);
             let reader = PrependReader::Prepend(reader);
-            let reader = AsyncDelimiterReader::new(reader, &delimiter);
-            let reader = CompressionReader::from_reader(entry_borrow.compression(), reader.take(u64::MAX));
+            let reader = BoundaryReader::new(reader);
+            let reader = CompressionReader::from_reader(entry_borrow, reader.take(u64::MAX));
 
-            Ok(Some(ZipEntryReader::with_data_descriptor(entry_borrow, reader, true)))
+            Ok(Some(ZipEntryReader::with_boundary(entry_borrow, reader)))
         } else {
             let reader = OwnedReader::Borrow(&mut self.reader);
             let reader = PrependReader::Prepend(reader);
-            let reader = reader.take(entry_borrow.compressed_size.unwrap().into());
-            let reader = CompressionReader::from_reader(entry_borrow.compression(), reader);
+            let reader = reader.take(entry_borrow.compressed_size.unwrap());
+            let reader = CompressionReader::from_reader(entry_borrow, reader);
 
             Ok(Some(ZipEntryReader::from_raw(entry_borrow, reader, true)))
         }
     }
 }
 
-pub(crate) async fn read_lfh<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Option<ZipEntry>> {
-    match reader.read_u32_le().await? {
-        crate::spec::signature::LOCAL_FILE_HEADER => {}
-        crate::spec::signature::CENTRAL_DIRECTORY_FILE_HEADER => return Ok(None),
-        actual => return Err(ZipError::UnexpectedHeaderError(actual, crate::spec::signature::LOCAL_FILE_HEADER)),
+// Read a local file header and its variable-length fields, or `None` if the central directory has been reached.
+//
+// The offset is that of the header's signature within the source, and is only used to report where a malformed header
+// was found.
+pub(crate) async fn read_lfh<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    offset: u64,
+    options: &ReaderOptions,
+) -> Result<Option<ZipEntry>> {
+    match reader.read_u32_le().await {
+        Ok(LOCAL_FILE_HEADER) => {}
+        Ok(CENTRAL_DIRECTORY_FILE_HEADER) => return Ok(None),
+        Ok(actual) => {
+            return Err(ZipError::UnexpectedHeaderAt(crate::spec::signature::name(LOCAL_FILE_HEADER), offset, actual))
+        }
+        Err(err) => return Err(truncated_at(LOCAL_FILE_HEADER, offset)(err.into())),
+    };
+
+    let header = LocalFileHeader::from_reader(reader).await.map_err(truncated_at(LOCAL_FILE_HEADER, offset))?;
+    let filename = async_io_utilities::read_bytes(reader, header.file_name_length.into())
+        .await
+        .map_err(|err| truncated_at(LOCAL_FILE_HEADER, offset)(err.into()))?;
+    let extra = async_io_utilities::read_bytes(reader, header.extra_field_length.into())
+        .await
+        .map_err(|err| truncated_at(LOCAL_FILE_HEADER, offset)(err.into()))?;
+
+    let filename = match parse_info_zip_unicode(&extra, INFO_ZIP_UNICODE_PATH, &filename) {
+        Some(filename) => filename,
+        None => options.decode_string(filename, header.flags.filename_unicode)?,
     };
 
-    let header = LocalFileHeader::from_reader(reader).await?;
-    let filename = async_io_utilities::read_string(reader, header.file_name_length.into()).await?;
-    let extra = async_io_utilities::read_bytes(reader, header.extra_field_length.into()).await?;
+    let mut uncompressed_size = header.uncompressed_size as u64;
+    let mut compressed_size = header.compressed_size as u64;
+
+    // A local file header's ZIP64 extended information must hold both sizes if either has overflowed.
+    if header.uncompressed_size == u32::MAX || header.compressed_size == u32::MAX {
+        let zip64 = parse_zip64_extended_information(&extra, true, true, false)?;
+
+        uncompressed_size = zip64.uncompressed_size.unwrap_or(uncompressed_size);
+        compressed_size = zip64.compressed_size.unwrap_or(compressed_size);
+    }
+
+    // These values are stored as zero within the header when they are instead deferred to a data descriptor.
+    let (crc32, uncompressed_size, compressed_size) = match header.flags.data_descriptor {
+        true => (None, None, None),
+        false => (Some(header.crc), Some(uncompressed_size), Some(compressed_size)),
+    };
 
     let entry = ZipEntry {
         name: filename,
         comment: None,
         data_descriptor: header.flags.data_descriptor,
-        crc32: Some(header.crc),
-        uncompressed_size: Some(header.uncompressed_size),
-        compressed_size: Some(header.compressed_size),
+        crc32,
+        uncompressed_size,
+        compressed_size,
         last_modified: crate::spec::date::zip_date_to_chrono(header.mod_date, header.mod_time),
+        mod_time: header.mod_time,
         extra: Some(extra),
         compression: Compression::from_u16(header.compression)?,
+        version_needed: header.version,
+        flags: header.flags,
         offset: None,
+        version_made_by: None,
+        internal_attributes: None,
+        external_attributes: None,
     };
 
     Ok(Some(entry))
@@ -17,6 +17,7 @@
 use crate::error::{Result, ZipError};
 use crate::read::{CompressionReader, OwnedReader, PrependReader, ZipEntry, ZipEntryReader};
 use crate::spec::header::LocalFileHeader;
+use crate::spec::signature::LOCAL_FILE_HEADER;
 
 use std::io::SeekFrom;
 use std::ops::DerefMut;
@@ -31,7 +32,8 @@ use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, ReadBuf};
 pub struct ZipFileReader<R: AsyncRead + AsyncSeek + Unpin> {
     pub(crate) reader: Arc<Mutex<R>>,
     pub(crate) entries: Vec<ZipEntry>,
-    pub(crate) comment: Option<String>,
+    pub(crate) comment: Option<crate::read::ArchiveComment>,
+    pub(crate) layout: crate::read::ArchiveLayout,
 }
 
 #[allow(unreachable_code, unused_variables)]
@@ -40,8 +42,9 @@ impl<R: AsyncRead + AsyncSeek + Unpin> ZipFileReader<R> {
     pub async fn new(reader: R) -> Result<ZipFileReader<R>> {
         unimplemented!();
 
-        let (entries, comment) = crate::read::seek::read_cd(&mut reader).await?;
-        Ok(ZipFileReader { reader: Arc::new(Mutex::new(reader)), entries, comment })
+        let (entries, comment, layout) =
+            crate::read::seek::read_cd(&mut reader, &crate::read::ReaderOptions::default()).await?;
+        Ok(ZipFileReader { reader: Arc::new(Mutex::new(reader)), entries, comment, layout })
     }
 
     crate::read::reader_entry_impl!();
@@ -51,9 +54,13 @@ impl<R: AsyncRead + AsyncSeek + Unpin> ZipFileReader<R> {
         let entry = self.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
 
         let mut guarded_reader = GuardedReader { reader: self.reader.clone() };
-        guarded_reader.seek(SeekFrom::Start(entry.offset.unwrap() as u64 + 4)).await?;
+        let offset = entry.offset.unwrap();
+        guarded_reader.seek(SeekFrom::Start(offset)).await?;
 
-        let header = LocalFileHeader::from_reader(&mut guarded_reader).await?;
+        crate::utils::assert_signature_at(&mut guarded_reader, LOCAL_FILE_HEADER, offset).await?;
+        let header = LocalFileHeader::from_reader(&mut guarded_reader)
+            .await
+            .map_err(crate::utils::truncated_at(LOCAL_FILE_HEADER, offset))?;
         let data_offset = (header.file_name_length + header.extra_field_length) as i64;
         guarded_reader.seek(SeekFrom::Current(data_offset)).await?;
 
@@ -62,14 +69,14 @@ impl<R: AsyncRead + AsyncSeek + Unpin> ZipFileReader<R> {
             let reader = OwnedReader::Owned(guarded_reader);
             let reader = PrependReader::Normal(reader);
             let reader = AsyncDelimiterReader::new(reader, &delimiter);
-            let reader = CompressionReader::from_reader(entry.compression(), reader.take(u64::MAX));
+            let reader = CompressionReader::from_reader(entry, reader.take(u64::MAX));
 
             Ok(ZipEntryReader::with_data_descriptor(entry, reader, true))
         } else {
             let reader = OwnedReader::Owned(guarded_reader);
             let reader = PrependReader::Normal(reader);
-            let reader = reader.take(entry.compressed_size.unwrap().into());
-            let reader = CompressionReader::from_reader(entry.compression(), reader);
+            let reader = reader.take(entry.compressed_size.unwrap());
+            let reader = CompressionReader::from_reader(entry, reader);
 
             Ok(ZipEntryReader::from_raw(entry, reader, false))
         }
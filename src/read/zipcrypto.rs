@@ -0,0 +1,106 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+// Decryption of entries encrypted with the traditional PKWARE encryption method (ie. ZipCrypto).
+//
+// Each encrypted entry's data is prefixed with a twelve byte encryption header. Once decrypted, the last byte of the
+// header holds a check byte which allows an incorrect password to be detected before any data is decompressed.
+//
+// https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#61
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::io::{AsyncRead, ReadBuf};
+
+pub(crate) const ENCRYPTION_HEADER_LENGTH: usize = 12;
+
+// The CRC32 lookup table, as the keys are updated with the raw (ie. non-inverted) CRC32 of a single byte.
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut index = 0;
+
+    while index < 256 {
+        let mut value = index as u32;
+        let mut bit = 0;
+
+        while bit < 8 {
+            value = match value & 1 {
+                1 => 0xEDB88320 ^ (value >> 1),
+                _ => value >> 1,
+            };
+            bit += 1;
+        }
+
+        table[index] = value;
+        index += 1;
+    }
+
+    table
+};
+
+fn crc32_byte(crc: u32, byte: u8) -> u32 {
+    CRC32_TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+}
+
+#[derive(Clone)]
+pub(crate) struct ZipCryptoKeys {
+    keys: [u32; 3],
+}
+
+impl ZipCryptoKeys {
+    pub(crate) fn new(password: &[u8]) -> Self {
+        let mut keys = ZipCryptoKeys { keys: [0x12345678, 0x23456789, 0x34567890] };
+
+        for byte in password {
+            keys.update(*byte);
+        }
+
+        keys
+    }
+
+    // Update the keys with a single byte of plaintext.
+    pub(crate) fn update(&mut self, byte: u8) {
+        self.keys[0] = crc32_byte(self.keys[0], byte);
+        self.keys[1] = self.keys[1].wrapping_add(self.keys[0] & 0xFF).wrapping_mul(134775813).wrapping_add(1);
+        self.keys[2] = crc32_byte(self.keys[2], (self.keys[1] >> 24) as u8);
+    }
+
+    // Returns the next byte of the keystream, which is XORed with a single byte of plaintext or ciphertext.
+    pub(crate) fn stream_byte(&self) -> u8 {
+        let temp = (self.keys[2] | 2) as u16;
+        (temp.wrapping_mul(temp ^ 1) >> 8) as u8
+    }
+
+    pub(crate) fn decrypt(&mut self, buffer: &mut [u8]) {
+        for byte in buffer {
+            *byte ^= self.stream_byte();
+            self.update(*byte);
+        }
+    }
+}
+
+// A reader which decrypts all bytes read from an inner reader, positioned just after the encryption header.
+pub(crate) struct ZipCryptoReader<R: AsyncRead + Unpin> {
+    inner: R,
+    keys: ZipCryptoKeys,
+}
+
+impl<R: AsyncRead + Unpin> ZipCryptoReader<R> {
+    pub(crate) fn new(inner: R, keys: ZipCryptoKeys) -> Self {
+        Self { inner, keys }
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for ZipCryptoReader<R> {
+    fn poll_read(mut self: Pin<&mut Self>, c: &mut Context<'_>, b: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        let prev_len = b.filled().len();
+        let poll = Pin::new(&mut self.inner).poll_read(c, b);
+
+        if let Poll::Ready(Ok(())) = poll {
+            self.keys.decrypt(&mut b.filled_mut()[prev_len..]);
+        }
+
+        poll
+    }
+}
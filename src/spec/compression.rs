@@ -17,6 +17,23 @@ pub enum Compression {
     Zstd,
     #[cfg(feature = "xz")]
     Xz,
+    /// The legacy Shrink method, which is only supported for reading.
+    #[cfg(feature = "legacy")]
+    Shrink,
+    /// The legacy Reduce method with a compression factor of between 1 and 4, which is only supported for reading.
+    #[cfg(feature = "legacy")]
+    Reduce(u8),
+}
+
+/// A Deflate compression option, as recorded within an entry's general purpose flag.
+///
+/// These options don't affect decompression and are purely informational.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeflateOption {
+    Normal,
+    Maximum,
+    Fast,
+    SuperFast,
 }
 
 impl Compression {
@@ -35,6 +52,10 @@ impl Compression {
             Compression::Zstd => 93,
             #[cfg(feature = "xz")]
             Compression::Xz => 95,
+            #[cfg(feature = "legacy")]
+            Compression::Shrink => 1,
+            #[cfg(feature = "legacy")]
+            Compression::Reduce(factor) => 1 + *factor as u16,
         }
     }
 
@@ -53,6 +74,10 @@ impl Compression {
             93 => Ok(Compression::Zstd),
             #[cfg(feature = "xz")]
             95 => Ok(Compression::Xz),
+            #[cfg(feature = "legacy")]
+            1 => Ok(Compression::Shrink),
+            #[cfg(feature = "legacy")]
+            2..=5 => Ok(Compression::Reduce((value - 1) as u8)),
             _ => Err(ZipError::UnsupportedCompressionError(value)),
         }
     }
@@ -11,7 +11,7 @@ pub fn zip_date_to_chrono(date: u16, time: u16) -> DateTime<Utc> {
     let months = ((date & 0x1E0) >> 5).into();
     let days = (date & 0x1F).into();
 
-    let hours = ((time & 0x1F) >> 11).into();
+    let hours = ((time & 0xF800) >> 11).into();
     let mins = ((time & 0x7E0) >> 5).into();
     let secs = ((time & 0x1F) << 1).into();
 
@@ -25,12 +25,21 @@ pub fn zip_date_to_chrono(date: u16, time: u16) -> DateTime<Utc> {
 }
 
 // Converts a `chrono` structure into a date and time stored in ZIP headers.
+//
+// The format can only represent years from 1980 to 2107 (inclusive), so earlier and later times are clamped to the
+// first and last representable times respectively.
 pub fn chrono_to_zip_time(dt: &DateTime<Utc>) -> (u16, u16) {
+    if dt.year() < 1980 {
+        return (0, (1 << 5) | 1);
+    } else if dt.year() > 2107 {
+        return ((23 << 11) | (59 << 5) | 29, (127 << 9) | (12 << 5) | 31);
+    }
+
     let year: u16 = (((dt.date().year() - 1980) << 9) & 0xFE00).try_into().unwrap();
     let month: u16 = ((dt.date().month() << 5) & 0x1E0).try_into().unwrap();
     let day: u16 = (dt.date().day() & 0x1F).try_into().unwrap();
 
-    let hour: u16 = ((dt.time().hour() << 11) & 0x1F).try_into().unwrap();
+    let hour: u16 = ((dt.time().hour() << 11) & 0xF800).try_into().unwrap();
     let min: u16 = ((dt.time().minute() << 5) & 0x7E0).try_into().unwrap();
     let second: u16 = ((dt.time().second() >> 1) & 0x1F).try_into().unwrap();
 
@@ -0,0 +1,162 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+use crate::error::{Result, ZipError};
+
+// https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#451
+
+/// A single record parsed from an entry's extra field bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtraField<'a> {
+    header_id: u16,
+    data: &'a [u8],
+}
+
+impl<'a> ExtraField<'a> {
+    /// Returns the header ID which identifies the type of this record.
+    pub fn header_id(&self) -> u16 {
+        self.header_id
+    }
+
+    /// Returns a shared reference to the record's data (excluding its header ID and length).
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+// Parse a sequence of (header ID, length, data) records from raw extra field bytes.
+//
+// Parsing stops at the first record which would overrun the available bytes, so any trailing malformed data is
+// ignored rather than treated as an error.
+pub(crate) fn parse_extra_fields(mut extra: &[u8]) -> Vec<ExtraField<'_>> {
+    let mut fields = Vec::new();
+
+    while extra.len() >= 4 {
+        let header_id = u16::from_le_bytes([extra[0], extra[1]]);
+        let length = u16::from_le_bytes([extra[2], extra[3]]) as usize;
+
+        if extra.len() - 4 < length {
+            break;
+        }
+
+        fields.push(ExtraField { header_id, data: &extra[4..4 + length] });
+        extra = &extra[4 + length..];
+    }
+
+    fields
+}
+
+// Append a single (header ID, length, data) record to a buffer of raw extra field bytes.
+pub(crate) fn push_extra_field(extra: &mut Vec<u8>, header_id: u16, data: &[u8]) {
+    extra.extend_from_slice(&header_id.to_le_bytes());
+    extra.extend_from_slice(&(data.len() as u16).to_le_bytes());
+    extra.extend_from_slice(data);
+}
+
+// https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#453
+pub(crate) const ZIP64_EXTENDED_INFORMATION: u16 = 0x0001;
+
+// The values parsed from a ZIP64 extended information record, where only those requested are present.
+pub(crate) struct Zip64ExtendedInformation {
+    pub(crate) uncompressed_size: Option<u64>,
+    pub(crate) compressed_size: Option<u64>,
+    pub(crate) lh_offset: Option<u64>,
+}
+
+// Parse a ZIP64 extended information record from raw extra field bytes.
+//
+// Each value is only present within the record when its corresponding header field holds a sentinel, but the values
+// which are present always appear in a fixed order. Thus, the caller must specify exactly which header fields held a
+// sentinel so that the correct bytes are attributed to each value.
+pub(crate) fn parse_zip64_extended_information(
+    extra: &[u8],
+    uncompressed_size: bool,
+    compressed_size: bool,
+    lh_offset: bool,
+) -> Result<Zip64ExtendedInformation> {
+    const MISSING: &str = "missing or truncated ZIP64 extended information";
+
+    let field = parse_extra_fields(extra).into_iter().find(|field| field.header_id == ZIP64_EXTENDED_INFORMATION);
+    let mut data = field.ok_or(ZipError::InvalidArchive(MISSING))?.data;
+
+    let mut next = |present: bool| -> Result<Option<u64>> {
+        if !present {
+            return Ok(None);
+        }
+        if data.len() < 8 {
+            return Err(ZipError::InvalidArchive(MISSING));
+        }
+
+        let value = u64::from_le_bytes(data[..8].try_into().unwrap());
+        data = &data[8..];
+        Ok(Some(value))
+    };
+
+    // Struct fields are evaluated in the order they're written, which is relied upon to match the record's ordering.
+    Ok(Zip64ExtendedInformation {
+        uncompressed_size: next(uncompressed_size)?,
+        compressed_size: next(compressed_size)?,
+        lh_offset: next(lh_offset)?,
+    })
+}
+
+// https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#468
+pub(crate) const INFO_ZIP_UNICODE_COMMENT: u16 = 0x6375;
+
+// https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#469
+pub(crate) const INFO_ZIP_UNICODE_PATH: u16 = 0x7075;
+
+// Parse an Info-ZIP Unicode path or comment record from raw extra field bytes, given the raw bytes of the header field
+// which it supersedes.
+//
+// The record holds a CRC32 value of the header field as it was when the record was written. If the header field has
+// since been changed (eg. by a tool which is unaware of the record), the record is stale and must be ignored, so
+// `None` is returned as it also is for a missing, unknown version, or non-UTF-8 record.
+pub(crate) fn parse_info_zip_unicode(extra: &[u8], header_id: u16, original: &[u8]) -> Option<String> {
+    let field = parse_extra_fields(extra).into_iter().find(|field| field.header_id == header_id)?;
+
+    if field.data.len() < 5 || field.data[0] != 1 {
+        return None;
+    }
+
+    let crc = u32::from_le_bytes(field.data[1..5].try_into().unwrap());
+
+    if crc != crc32fast::hash(original) {
+        return None;
+    }
+
+    String::from_utf8(field.data[5..].to_vec()).ok()
+}
+
+// https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#455
+pub(crate) const NTFS: u16 = 0x000a;
+
+// https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#461
+pub(crate) const EXTENDED_TIMESTAMP: u16 = 0x5455;
+
+// The NTFS record's attribute tag which holds the modification, access, and creation times.
+const NTFS_TIMES_TAG: u16 = 0x0001;
+
+// Parse the creation time from an NTFS record within raw extra field bytes.
+//
+// The time is returned as stored (ie. the number of 100 nanosecond intervals since January 1, 1601 UTC), and `None`
+// is returned for a missing or truncated record, or one without a times attribute.
+pub(crate) fn parse_ntfs_creation_time(extra: &[u8]) -> Option<u64> {
+    let field = parse_extra_fields(extra).into_iter().find(|field| field.header_id == NTFS)?;
+    // Skip the four reserved bytes which precede the attributes.
+    let mut data = field.data.get(4..)?;
+
+    while data.len() >= 4 {
+        let tag = u16::from_le_bytes([data[0], data[1]]);
+        let size = u16::from_le_bytes([data[2], data[3]]) as usize;
+        let value = data.get(4..4 + size)?;
+
+        if tag == NTFS_TIMES_TAG && size >= 24 {
+            return Some(u64::from_le_bytes(value[16..24].try_into().unwrap()));
+        }
+
+        data = &data[4 + size..];
+    }
+
+    None
+}
@@ -16,9 +16,10 @@ pub struct LocalFileHeader {
 }
 
 // https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#444
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub struct GeneralPurposeFlag {
     pub encrypted: bool,
+    pub compression_options: u8,
     pub data_descriptor: bool,
     pub filename_unicode: bool,
 }
@@ -53,3 +54,20 @@ pub struct EndOfCentralDirectoryHeader {
     pub(crate) cent_dir_offset: u32,
     pub(crate) file_comm_length: u16,
 }
+
+// https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#4314
+pub struct Zip64EndOfCentralDirectoryRecord {
+    pub(crate) disk_num: u32,
+    pub(crate) start_cent_dir_disk: u32,
+    pub(crate) num_of_entries_disk: u64,
+    pub(crate) num_of_entries: u64,
+    pub(crate) size_cent_dir: u64,
+    pub(crate) cent_dir_offset: u64,
+}
+
+// https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#4315
+pub struct Zip64EndOfCentralDirectoryLocator {
+    pub(crate) start_eocdr_disk: u32,
+    pub(crate) eocdr_offset: u64,
+    pub(crate) total_disks: u32,
+}
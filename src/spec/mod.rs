@@ -3,6 +3,7 @@
 
 pub(crate) mod compression;
 pub(crate) mod date;
+pub(crate) mod extra_field;
 pub(crate) mod header;
 pub(crate) mod parse;
 pub(crate) mod signature;
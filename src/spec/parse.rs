@@ -2,7 +2,10 @@
 // MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
 
 use crate::error::Result;
-use crate::spec::header::{CentralDirectoryHeader, EndOfCentralDirectoryHeader, GeneralPurposeFlag, LocalFileHeader};
+use crate::spec::header::{
+    CentralDirectoryHeader, EndOfCentralDirectoryHeader, GeneralPurposeFlag, LocalFileHeader,
+    Zip64EndOfCentralDirectoryLocator, Zip64EndOfCentralDirectoryRecord,
+};
 
 use tokio::io::{AsyncRead, AsyncReadExt};
 
@@ -36,12 +39,13 @@ impl GeneralPurposeFlag {
             false => 0x0,
             true => 0x8,
         };
+        let compression_options = (self.compression_options as u16 & 0b11) << 1;
         let filename_unicode: u16 = match self.filename_unicode {
             false => 0x0,
             true => 0x800,
         };
 
-        (encrypted | data_descriptor | filename_unicode).to_le_bytes()
+        (encrypted | compression_options | data_descriptor | filename_unicode).to_le_bytes()
     }
 }
 
@@ -108,10 +112,11 @@ impl From<[u8; 26]> for LocalFileHeader {
 impl From<u16> for GeneralPurposeFlag {
     fn from(value: u16) -> GeneralPurposeFlag {
         let encrypted = !matches!(value & 0x1, 0);
+        let compression_options = ((value & 0x6) >> 1) as u8;
         let data_descriptor = !matches!((value & 0x8) >> 3, 0);
         let filename_unicode = !matches!((value & 0x800) >> 11, 0);
 
-        GeneralPurposeFlag { encrypted, data_descriptor, filename_unicode }
+        GeneralPurposeFlag { encrypted, compression_options, data_descriptor, filename_unicode }
     }
 }
 
@@ -152,6 +157,30 @@ impl From<[u8; 18]> for EndOfCentralDirectoryHeader {
     }
 }
 
+impl From<[u8; 52]> for Zip64EndOfCentralDirectoryRecord {
+    fn from(value: [u8; 52]) -> Zip64EndOfCentralDirectoryRecord {
+        // The record's size and version fields (the first 12 bytes) aren't currently of any use to us.
+        Zip64EndOfCentralDirectoryRecord {
+            disk_num: u32::from_le_bytes(value[12..16].try_into().unwrap()),
+            start_cent_dir_disk: u32::from_le_bytes(value[16..20].try_into().unwrap()),
+            num_of_entries_disk: u64::from_le_bytes(value[20..28].try_into().unwrap()),
+            num_of_entries: u64::from_le_bytes(value[28..36].try_into().unwrap()),
+            size_cent_dir: u64::from_le_bytes(value[36..44].try_into().unwrap()),
+            cent_dir_offset: u64::from_le_bytes(value[44..52].try_into().unwrap()),
+        }
+    }
+}
+
+impl From<[u8; 16]> for Zip64EndOfCentralDirectoryLocator {
+    fn from(value: [u8; 16]) -> Zip64EndOfCentralDirectoryLocator {
+        Zip64EndOfCentralDirectoryLocator {
+            start_eocdr_disk: u32::from_le_bytes(value[0..4].try_into().unwrap()),
+            eocdr_offset: u64::from_le_bytes(value[4..12].try_into().unwrap()),
+            total_disks: u32::from_le_bytes(value[12..16].try_into().unwrap()),
+        }
+    }
+}
+
 impl LocalFileHeader {
     pub async fn from_reader<R: AsyncRead + Unpin>(reader: &mut R) -> Result<LocalFileHeader> {
         let mut buffer: [u8; 26] = [0; 26];
@@ -176,6 +205,22 @@ impl CentralDirectoryHeader {
     }
 }
 
+impl Zip64EndOfCentralDirectoryRecord {
+    pub async fn from_reader<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Zip64EndOfCentralDirectoryRecord> {
+        let mut buffer: [u8; 52] = [0; 52];
+        reader.read_exact(&mut buffer).await?;
+        Ok(Zip64EndOfCentralDirectoryRecord::from(buffer))
+    }
+}
+
+impl Zip64EndOfCentralDirectoryLocator {
+    pub async fn from_reader<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Zip64EndOfCentralDirectoryLocator> {
+        let mut buffer: [u8; 16] = [0; 16];
+        reader.read_exact(&mut buffer).await?;
+        Ok(Zip64EndOfCentralDirectoryLocator::from(buffer))
+    }
+}
+
 /// Replace elements of an array at a given cursor index for use with a zero-initialised array.
 macro_rules! array_push {
     ($arr:ident, $cursor:ident, $value:expr) => {{
@@ -12,3 +12,22 @@ pub const DATA_DESCRIPTOR: u32 = 0x8074b50;
 
 // https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#4316
 pub const END_OF_CENTRAL_DIRECTORY: u32 = 0x6054b50;
+
+// https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#4314
+pub const ZIP64_END_OF_CENTRAL_DIRECTORY: u32 = 0x6064b50;
+
+// https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#4315
+pub const ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR: u32 = 0x7064b50;
+
+// Returns the name of the structure identified by a signature, for use within error messages.
+pub(crate) fn name(signature: u32) -> &'static str {
+    match signature {
+        LOCAL_FILE_HEADER => "local file header",
+        CENTRAL_DIRECTORY_FILE_HEADER => "central directory file header",
+        DATA_DESCRIPTOR => "data descriptor",
+        END_OF_CENTRAL_DIRECTORY => "end of central directory record",
+        ZIP64_END_OF_CENTRAL_DIRECTORY => "ZIP64 end of central directory record",
+        ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR => "ZIP64 end of central directory locator",
+        _ => "header",
+    }
+}
@@ -7,6 +7,94 @@ use crate::write::EntryOptions;
 
 pub(crate) const SPEC_VERSION_MADE_BY: u16 = 63;
 
+/// The host system which produced an entry, as stored within the upper byte of its "version made by" value.
+///
+/// The host determines how an entry's external file attributes are interpreted (eg. Unix permissions are only stored
+/// by Unix-like hosts).
+///
+/// https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#442
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostSystem {
+    MsDos,
+    Amiga,
+    OpenVms,
+    Unix,
+    VmCms,
+    AtariSt,
+    Os2Hpfs,
+    Macintosh,
+    ZSystem,
+    CpM,
+    WindowsNtfs,
+    Mvs,
+    Vse,
+    AcornRisc,
+    Vfat,
+    AlternateMvs,
+    BeOs,
+    Tandem,
+    Os400,
+    Darwin,
+    /// A host identifier which is unused by the specification.
+    Unknown(u8),
+}
+
+impl HostSystem {
+    /// Convert a host system into its identifier.
+    pub fn to_u8(&self) -> u8 {
+        match self {
+            HostSystem::MsDos => 0,
+            HostSystem::Amiga => 1,
+            HostSystem::OpenVms => 2,
+            HostSystem::Unix => 3,
+            HostSystem::VmCms => 4,
+            HostSystem::AtariSt => 5,
+            HostSystem::Os2Hpfs => 6,
+            HostSystem::Macintosh => 7,
+            HostSystem::ZSystem => 8,
+            HostSystem::CpM => 9,
+            HostSystem::WindowsNtfs => 10,
+            HostSystem::Mvs => 11,
+            HostSystem::Vse => 12,
+            HostSystem::AcornRisc => 13,
+            HostSystem::Vfat => 14,
+            HostSystem::AlternateMvs => 15,
+            HostSystem::BeOs => 16,
+            HostSystem::Tandem => 17,
+            HostSystem::Os400 => 18,
+            HostSystem::Darwin => 19,
+            HostSystem::Unknown(value) => *value,
+        }
+    }
+
+    /// Convert an identifier into its host system.
+    pub fn from_u8(value: u8) -> HostSystem {
+        match value {
+            0 => HostSystem::MsDos,
+            1 => HostSystem::Amiga,
+            2 => HostSystem::OpenVms,
+            3 => HostSystem::Unix,
+            4 => HostSystem::VmCms,
+            5 => HostSystem::AtariSt,
+            6 => HostSystem::Os2Hpfs,
+            7 => HostSystem::Macintosh,
+            8 => HostSystem::ZSystem,
+            9 => HostSystem::CpM,
+            10 => HostSystem::WindowsNtfs,
+            11 => HostSystem::Mvs,
+            12 => HostSystem::Vse,
+            13 => HostSystem::AcornRisc,
+            14 => HostSystem::Vfat,
+            15 => HostSystem::AlternateMvs,
+            16 => HostSystem::BeOs,
+            17 => HostSystem::Tandem,
+            18 => HostSystem::Os400,
+            19 => HostSystem::Darwin,
+            value => HostSystem::Unknown(value),
+        }
+    }
+}
+
 // https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#443
 pub fn as_needed_to_extract(options: &EntryOptions) -> u16 {
     let mut version = match options.compression {
@@ -28,6 +116,26 @@ pub fn as_needed_to_extract(options: &EntryOptions) -> u16 {
 
 // https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#442
 pub fn as_made_by() -> u16 {
-    // Default to UNIX mapping for the moment.
-    3 << 8 | SPEC_VERSION_MADE_BY
+    // Default to UNIX mapping unless overridden via ZipFileWriter::set_version_made_by().
+    with_host(HostSystem::Unix, SPEC_VERSION_MADE_BY as u8)
+}
+
+pub(crate) fn with_host(host: HostSystem, version: u8) -> u16 {
+    (host.to_u8() as u16) << 8 | version as u16
+}
+
+// https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#443
+//
+// Returns a description of the feature implied by a "version needed to extract" value which we don't support, if
+// any. Features which are detected by other means (eg. compression methods or ZIP64 records) aren't listed.
+pub(crate) fn as_unsupported_feature(version_needed: u16) -> Option<&'static str> {
+    match version_needed & 0xFF {
+        version if version > 63 => Some("Version needed to extract above 6.3"),
+        62 => Some("Version needed to extract 6.2 (central directory encryption)"),
+        61 => Some("Version needed to extract 6.1 (non-OAEP key wrapping)"),
+        52 => Some("Version needed to extract 5.2 (RC2-64 encryption)"),
+        51 => Some("Version needed to extract 5.1 (AES encryption)"),
+        50 => Some("Version needed to extract 5.0 (DES, 3DES, RC2, or RC4 encryption)"),
+        _ => None,
+    }
 }
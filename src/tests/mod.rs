@@ -103,7 +103,7 @@ async fn data_descriptor_single() {
     assert_eq!(0, entry.0);
     assert!(entry.1.compressed_size().is_some());
     assert!(entry.1.data_descriptor);
-    assert_eq!(data.len() as u32, entry.1.uncompressed_size().expect("no uncompressed size"));
+    assert_eq!(data.len() as u64, entry.1.uncompressed_size().expect("no uncompressed size"));
     assert_eq!(Compression::Deflate, *entry.1.compression());
 
     let entry_reader = zip_reader.entry_reader(0).await.expect("failed to open entry reader");
@@ -186,7 +186,7 @@ macro_rules! single_entry_gen {
             let entry = zip_reader.entry("foo.bar").expect("no 'foo.bar' entry");
             assert_eq!(0, entry.0);
             assert!(entry.1.compressed_size().is_some());
-            assert_eq!(data.len() as u32, entry.1.uncompressed_size().expect("no uncompressed size"));
+            assert_eq!(data.len() as u64, entry.1.uncompressed_size().expect("no uncompressed size"));
             assert_eq!($typ, *entry.1.compression());
 
             let entry_reader = zip_reader.entry_reader(0).await.expect("failed to open entry reader");
@@ -208,3 +208,2455 @@ single_entry_gen!(single_entry_lzma, Compression::Lzma);
 single_entry_gen!(single_entry_zstd, Compression::Zstd);
 #[cfg(feature = "xz")]
 single_entry_gen!(single_entry_xz, Compression::Xz);
+
+#[tokio::test]
+async fn physical_ordering() {
+    use crate::read::seek::ZipFileReader;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    for name in ["foo.bar", "bar.foo"] {
+        let open_opts = EntryOptions::new(name.to_string(), Compression::Stored);
+        zip_writer.write_entry_whole(open_opts, name.as_bytes()).await.expect("failed to write entry");
+    }
+
+    zip_writer.close().await.expect("failed to close writer");
+    input_stream.set_position(0);
+
+    let zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+    assert!(zip_reader.is_physically_ordered());
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    for name in ["foo.bar", "bar.foo"] {
+        let open_opts = EntryOptions::new(name.to_string(), Compression::Stored);
+        zip_writer.write_entry_whole(open_opts, name.as_bytes()).await.expect("failed to write entry");
+    }
+
+    // Reverse the central directory so that it no longer matches the order of the local file headers.
+    zip_writer.cd_entries.reverse();
+    zip_writer.close().await.expect("failed to close writer");
+    input_stream.set_position(0);
+
+    let mut zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+    assert!(!zip_reader.is_physically_ordered());
+    assert_eq!("bar.foo", zip_reader.entries()[0].name());
+
+    let entry_reader = zip_reader.entry_reader(0).await.expect("failed to open entry reader");
+    let buffer = entry_reader.read_to_string_crc().await.expect("failed to read entry to string");
+    assert_eq!("bar.foo", buffer);
+}
+
+#[tokio::test]
+async fn extra_field_round_trip() {
+    use crate::error::ZipError;
+    use crate::read::seek::ZipFileReader;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    let open_opts = EntryOptions::new("foo.bar".to_string(), Compression::Stored)
+        .extra_field(0xCAFE, vec![1, 2, 3, 4])
+        .extra_field(0xD935, vec![0; 6]);
+    zip_writer.write_entry_whole(open_opts, b"foo").await.expect("failed to write entry");
+
+    let open_opts = EntryOptions::new("bar.foo".to_string(), Compression::Stored).extra_field(0xCAFE, vec![0; 65532]);
+    let result = zip_writer.write_entry_whole(open_opts, b"bar").await;
+    assert!(matches!(result, Err(ZipError::ExtraFieldTooLarge)));
+
+    zip_writer.close().await.expect("failed to close writer");
+    input_stream.set_position(0);
+
+    let zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+    assert_eq!(1, zip_reader.entries().len());
+
+    let fields = zip_reader.entries()[0].extra_fields();
+    assert_eq!(2, fields.len());
+    assert_eq!(0xCAFE, fields[0].header_id());
+    assert_eq!(&[1u8, 2, 3, 4], fields[0].data());
+    assert_eq!(0xD935, fields[1].header_id());
+    assert_eq!(&[0u8; 6], fields[1].data());
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn entry_reader_limit() {
+    use crate::read::seek::ZipFileReader;
+    use tokio::io::AsyncReadExt;
+
+    let data: Vec<u8> = (0..u16::MAX).flat_map(|value| value.to_le_bytes()).collect();
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    let open_opts = EntryOptions::new("foo.bar".to_string(), Compression::Deflate);
+    zip_writer.write_entry_whole(open_opts, &data).await.expect("failed to write entry");
+    zip_writer.close().await.expect("failed to close writer");
+
+    input_stream.set_position(0);
+    let mut zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+
+    let mut limited_reader = zip_reader.entry_reader(0).await.expect("failed to open entry reader").limit(100);
+    let mut buffer = Vec::new();
+    limited_reader.read_to_end(&mut buffer).await.expect("failed to read limited entry");
+
+    assert_eq!(&data[..100], buffer);
+    assert_eq!(0, limited_reader.remaining());
+    drop(limited_reader);
+
+    let entry_reader = zip_reader.entry_reader(0).await.expect("failed to open entry reader");
+    let buffer = entry_reader.read_to_end_crc().await.expect("failed to read entry");
+    assert_eq!(data, buffer);
+}
+
+// Replace the end of central directory record of a comment-less archive with one which defers its entry count to a
+// ZIP64 end of central directory record, whilst keeping the real central directory size and offset.
+fn overflow_entry_count(mut archive: Vec<u8>, num_of_entries: u64) -> Vec<u8> {
+    let eocdr_offset = archive.len() - 22;
+    let size_cent_dir = u32::from_le_bytes(archive[eocdr_offset + 12..eocdr_offset + 16].try_into().unwrap());
+    let cent_dir_offset = u32::from_le_bytes(archive[eocdr_offset + 16..eocdr_offset + 20].try_into().unwrap());
+    archive.truncate(eocdr_offset);
+
+    archive.extend_from_slice(&crate::spec::signature::ZIP64_END_OF_CENTRAL_DIRECTORY.to_le_bytes());
+    archive.extend_from_slice(&44u64.to_le_bytes());
+    archive.extend_from_slice(&45u16.to_le_bytes());
+    archive.extend_from_slice(&45u16.to_le_bytes());
+    archive.extend_from_slice(&0u32.to_le_bytes());
+    archive.extend_from_slice(&0u32.to_le_bytes());
+    archive.extend_from_slice(&num_of_entries.to_le_bytes());
+    archive.extend_from_slice(&num_of_entries.to_le_bytes());
+    archive.extend_from_slice(&(size_cent_dir as u64).to_le_bytes());
+    archive.extend_from_slice(&(cent_dir_offset as u64).to_le_bytes());
+
+    archive.extend_from_slice(&crate::spec::signature::ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR.to_le_bytes());
+    archive.extend_from_slice(&0u32.to_le_bytes());
+    archive.extend_from_slice(&(eocdr_offset as u64).to_le_bytes());
+    archive.extend_from_slice(&1u32.to_le_bytes());
+
+    archive.extend_from_slice(&crate::spec::signature::END_OF_CENTRAL_DIRECTORY.to_le_bytes());
+    archive.extend_from_slice(&0u16.to_le_bytes());
+    archive.extend_from_slice(&0u16.to_le_bytes());
+    archive.extend_from_slice(&u16::MAX.to_le_bytes());
+    archive.extend_from_slice(&u16::MAX.to_le_bytes());
+    archive.extend_from_slice(&size_cent_dir.to_le_bytes());
+    archive.extend_from_slice(&cent_dir_offset.to_le_bytes());
+    archive.extend_from_slice(&0u16.to_le_bytes());
+
+    archive
+}
+
+#[tokio::test]
+async fn zip64_partial_overflow() {
+    use crate::read::seek::ZipFileReader;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    for name in ["foo.bar", "bar.foo"] {
+        let open_opts = EntryOptions::new(name.to_string(), Compression::Stored);
+        zip_writer.write_entry_whole(open_opts, name.as_bytes()).await.expect("failed to write entry");
+    }
+
+    zip_writer.close().await.expect("failed to close writer");
+
+    let mut input_stream = Cursor::new(overflow_entry_count(input_stream.into_inner(), 2));
+    let mut zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+
+    assert_eq!(2, zip_reader.entries().len());
+    assert_eq!("bar.foo", zip_reader.entries()[1].name());
+
+    let entry_reader = zip_reader.entry_reader(1).await.expect("failed to open entry reader");
+    let buffer = entry_reader.read_to_string_crc().await.expect("failed to read entry to string");
+    assert_eq!("bar.foo", buffer);
+}
+
+#[tokio::test]
+async fn memory_usage() {
+    use crate::read::seek::ZipFileReader;
+
+    let mut usages = Vec::new();
+
+    for count in [1, 10, 100] {
+        let mut input_stream = Cursor::new(Vec::<u8>::new());
+        let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+        for index in 0..count {
+            let open_opts = EntryOptions::new(format!("{index}.txt"), Compression::Stored).comment("Foo".to_string());
+            zip_writer.write_entry_whole(open_opts, b"bar").await.expect("failed to write entry");
+        }
+
+        zip_writer.close().await.expect("failed to close writer");
+        input_stream.set_position(0);
+
+        let zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+        usages.push(zip_reader.memory_usage());
+    }
+
+    assert!(usages[0] > 0);
+    assert!(usages[0] < usages[1]);
+    assert!(usages[1] < usages[2]);
+}
+
+#[tokio::test]
+async fn unsafe_entry_names() {
+    use crate::error::ZipError;
+    use crate::read::seek::ZipFileReader;
+
+    let names = ["foo/./bar.txt", "evil.sh\0.txt", "line\nbreak.txt", "../evil.txt", "/etc/passwd", "foo/../../bar"];
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    for name in names {
+        let open_opts = EntryOptions::new(name.to_string(), Compression::Stored);
+        zip_writer.write_entry_whole(open_opts, b"foo").await.expect("failed to write entry");
+    }
+
+    zip_writer.close().await.expect("failed to close writer");
+    input_stream.set_position(0);
+
+    let zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+    let entries = zip_reader.entries();
+
+    assert!(!entries[0].has_control_chars());
+    assert_eq!(std::path::Path::new("foo").join("bar.txt"), entries[0].safe_path().expect("path deemed unsafe"));
+
+    assert!(entries[1].has_control_chars());
+    assert_eq!("evil.sh\0.txt", entries[1].name());
+    assert!(entries[2].has_control_chars());
+
+    for entry in &entries[1..] {
+        assert!(matches!(entry.safe_path(), Err(ZipError::UnsafePath(name)) if name == entry.name()));
+    }
+}
+
+#[tokio::test]
+async fn seek_to_entry_raw_data() {
+    use crate::read::seek::ZipFileReader;
+    use tokio::io::AsyncReadExt;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    for name in ["foo.bar", "bar.foo"] {
+        let open_opts = EntryOptions::new(name.to_string(), Compression::Stored);
+        zip_writer.write_entry_whole(open_opts, name.as_bytes()).await.expect("failed to write entry");
+    }
+
+    zip_writer.close().await.expect("failed to close writer");
+    input_stream.set_position(0);
+
+    let mut zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+    let (source, length) = zip_reader.seek_to_entry(1).await.expect("failed to seek to entry");
+
+    let mut buffer = vec![0; length as usize];
+    source.read_exact(&mut buffer).await.expect("failed to read raw entry data");
+    assert_eq!(b"bar.foo", &buffer[..]);
+}
+
+// Build a raw central directory record with the given (possibly sentinel) header values and ZIP64 record values.
+fn zip64_cd_entry(uncompressed_size: u32, compressed_size: u32, lh_offset: u32, zip64: &[u64]) -> Vec<u8> {
+    use crate::spec::header::{CentralDirectoryHeader, GeneralPurposeFlag};
+
+    let data: Vec<u8> = zip64.iter().flat_map(|value| value.to_le_bytes()).collect();
+    let mut extra = Vec::new();
+    crate::spec::extra_field::push_extra_field(&mut extra, 0xCAFE, &[0; 3]);
+    crate::spec::extra_field::push_extra_field(&mut extra, 0x0001, &data);
+
+    let header = CentralDirectoryHeader {
+        v_made_by: crate::spec::version::as_made_by(),
+        v_needed: 45,
+        flags: GeneralPurposeFlag::from(0u16),
+        compression: Compression::Stored.to_u16(),
+        mod_time: 0,
+        mod_date: 0,
+        crc: 0,
+        compressed_size,
+        uncompressed_size,
+        file_name_length: 7,
+        extra_field_length: extra.len() as u16,
+        file_comment_length: 0,
+        disk_start: 0,
+        inter_attr: 0,
+        exter_attr: 0,
+        lh_offset,
+    };
+
+    let mut record = crate::spec::signature::CENTRAL_DIRECTORY_FILE_HEADER.to_le_bytes().to_vec();
+    record.extend_from_slice(&header.as_slice());
+    record.extend_from_slice(b"foo.bar");
+    record.extend_from_slice(&extra);
+    record
+}
+
+#[tokio::test]
+async fn zip64_extended_information_subsets() {
+    use crate::read::seek::read_cd_entry;
+    use crate::read::ReaderOptions;
+
+    const LARGE: u64 = 0x1_0000_0000;
+
+    // Only the offset has overflowed, so the record holds only the offset.
+    let record = zip64_cd_entry(10, 8, u32::MAX, &[LARGE + 2]);
+    let entry = read_cd_entry(&mut Cursor::new(record), &ReaderOptions::default(), 0, &mut u64::MAX)
+        .await
+        .expect("failed to read entry");
+    assert_eq!(Some(10), entry.uncompressed_size());
+    assert_eq!(Some(8), entry.compressed_size());
+    assert_eq!(Some(LARGE + 2), entry.offset);
+
+    // Only the compressed size has overflowed.
+    let record = zip64_cd_entry(10, u32::MAX, 6, &[LARGE + 1]);
+    let entry = read_cd_entry(&mut Cursor::new(record), &ReaderOptions::default(), 0, &mut u64::MAX)
+        .await
+        .expect("failed to read entry");
+    assert_eq!(Some(10), entry.uncompressed_size());
+    assert_eq!(Some(LARGE + 1), entry.compressed_size());
+    assert_eq!(Some(6), entry.offset);
+
+    // The uncompressed size and offset have overflowed, so the compressed size is skipped within the record.
+    let record = zip64_cd_entry(u32::MAX, 8, u32::MAX, &[LARGE, LARGE + 2]);
+    let entry = read_cd_entry(&mut Cursor::new(record), &ReaderOptions::default(), 0, &mut u64::MAX)
+        .await
+        .expect("failed to read entry");
+    assert_eq!(Some(LARGE), entry.uncompressed_size());
+    assert_eq!(Some(8), entry.compressed_size());
+    assert_eq!(Some(LARGE + 2), entry.offset);
+
+    // A record which is too short for the fields which have overflowed is rejected.
+    let record = zip64_cd_entry(u32::MAX, u32::MAX, 6, &[LARGE]);
+    assert!(read_cd_entry(&mut Cursor::new(record), &ReaderOptions::default(), 0, &mut u64::MAX).await.is_err());
+}
+
+// A writer which shares its written bytes so that they may be observed whilst a ZIP file writer holds it.
+#[derive(Clone, Default)]
+struct SharedSink {
+    buffer: std::sync::Arc<std::sync::Mutex<Vec<u8>>>,
+}
+
+impl SharedSink {
+    fn len(&self) -> usize {
+        self.buffer.lock().unwrap().len()
+    }
+}
+
+impl tokio::io::AsyncWrite for SharedSink {
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        _: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        self.buffer.lock().unwrap().extend_from_slice(buf);
+        std::task::Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        _: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(
+        self: std::pin::Pin<&mut Self>,
+        _: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn flush_before_close() {
+    use tokio::io::AsyncWriteExt;
+
+    let sink = SharedSink::default();
+    let mut zip_writer = ZipFileWriter::new(sink.clone());
+    let data = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt...";
+
+    let open_opts = EntryOptions::new("foo.bar".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(open_opts, data.as_bytes()).await.expect("failed to write entry");
+    zip_writer.flush().await.expect("failed to flush writer");
+
+    let first_len = sink.len();
+    assert!(first_len >= 30 + "foo.bar".len() + data.len());
+
+    let open_opts = EntryOptions::new("bar.foo".to_string(), Compression::Deflate);
+    let mut entry_writer = zip_writer.write_entry_stream(open_opts).await.expect("failed to open write entry");
+    entry_writer.write_all(data.as_bytes()).await.expect("failed to write entry");
+    entry_writer.close().await.expect("failed to close entry");
+    zip_writer.flush().await.expect("failed to flush writer");
+
+    let second_len = sink.len();
+    assert!(second_len > first_len + 30 + "bar.foo".len());
+
+    zip_writer.close().await.expect("failed to close writer");
+    assert!(sink.len() > second_len);
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn data_descriptor_central_crc() {
+    use crate::error::ZipError;
+    use crate::read::seek::ZipFileReader;
+    use tokio::io::AsyncWriteExt;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+    let data = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt...";
+
+    let open_opts = EntryOptions::new("foo.bar".to_string(), Compression::Deflate);
+    let mut entry_writer = zip_writer.write_entry_stream(open_opts).await.expect("failed to open write entry");
+    entry_writer.write_all(data.as_bytes()).await.expect("failed to write entry");
+    entry_writer.close().await.expect("failed to close entry");
+    zip_writer.close().await.expect("failed to close writer");
+
+    let mut archive = input_stream.into_inner();
+
+    // The local file header's CRC32 value is zero as it was unknown at the time of writing.
+    assert_eq!(&[0u8; 4], &archive[14..18]);
+
+    let mut zip_reader = ZipFileReader::new(Cursor::new(archive.clone())).await.expect("failed to open reader");
+    assert_ne!(Some(0), zip_reader.entries()[0].crc32());
+
+    let entry_reader = zip_reader.entry_reader(0).await.expect("failed to open entry reader");
+    let buffer = entry_reader.read_to_string_crc().await.expect("failed to read entry to string");
+    assert_eq!(data, buffer);
+
+    // Corrupt the data descriptor's CRC32 value, leaving the central directory's value intact.
+    let signature = crate::spec::signature::DATA_DESCRIPTOR.to_le_bytes();
+    let position = archive.windows(4).position(|window| window == signature).expect("no data descriptor");
+    archive[position + 4] ^= 0xFF;
+
+    let mut zip_reader = ZipFileReader::new(Cursor::new(archive)).await.expect("failed to open reader");
+    let entry_reader = zip_reader.entry_reader(0).await.expect("failed to open entry reader");
+    let result = entry_reader.read_to_string_crc().await;
+    assert!(matches!(result, Err(ZipError::DataDescriptorCRC32Mismatch(_, _))));
+}
+
+#[tokio::test]
+async fn reader_capacity_hint() {
+    use crate::read::mem::ZipFileReader;
+    use crate::read::ReaderOptions;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    for index in 0..3 {
+        let opts = EntryOptions::new(format!("{}.txt", index), Compression::Stored);
+        zip_writer.write_entry_whole(opts, b"data").await.expect("failed to write entry");
+    }
+    zip_writer.close().await.expect("failed to close writer");
+
+    let archive = input_stream.into_inner();
+    let options = ReaderOptions::new().capacity_hint(64);
+    let zip_reader = ZipFileReader::with_options(&archive, options).await.expect("failed to open reader");
+
+    assert_eq!(3, zip_reader.entries().len());
+    assert!(zip_reader.memory_usage() >= 64 * std::mem::size_of::<crate::read::ZipEntry>());
+}
+
+#[tokio::test]
+async fn shift_jis_filename_encoding() {
+    use crate::read::mem::ZipFileReader;
+    use crate::read::ReaderOptions;
+
+    // "日本語" encoded as Shift-JIS.
+    const SHIFT_JIS_NAME: [u8; 6] = [0x93, 0xFA, 0x96, 0x7B, 0x8C, 0xEA];
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    // A non-ASCII name is written with the UTF-8 flag set, whereas an ASCII placeholder is not.
+    let opts = EntryOptions::new("日本語.txt".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, b"utf-8").await.expect("failed to write entry");
+    let opts = EntryOptions::new("abcdef.txt".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, b"shift-jis").await.expect("failed to write entry");
+    zip_writer.close().await.expect("failed to close writer");
+
+    // Replace the placeholder with its Shift-JIS counterpart of the same length in both headers.
+    let mut archive = input_stream.into_inner();
+    while let Some(position) = archive.windows(6).position(|window| window == b"abcdef") {
+        archive[position..position + 6].copy_from_slice(&SHIFT_JIS_NAME);
+    }
+
+    assert!(ZipFileReader::new(&archive).await.is_err());
+
+    let options = ReaderOptions::new().filename_encoding(encoding_rs::SHIFT_JIS);
+    let zip_reader = ZipFileReader::with_options(&archive, options).await.expect("failed to open reader");
+
+    assert_eq!("日本語.txt", zip_reader.entries()[0].name());
+    assert_eq!("日本語.txt", zip_reader.entries()[1].name());
+
+    // The local file headers are decoded in the same way when read from a non-seekable source.
+    let options = ReaderOptions::new().filename_encoding(encoding_rs::SHIFT_JIS);
+    let mut zip_reader = crate::read::stream::ZipFileReader::with_options(Cursor::new(&archive), options);
+
+    for expected in ["日本語.txt", "日本語.txt"] {
+        let entry_reader = zip_reader.entry_reader().await.expect("failed to read entry").expect("no entry");
+        assert_eq!(expected, entry_reader.entry().name());
+        entry_reader.read_to_end_crc().await.expect("failed to read entry");
+    }
+    assert!(zip_reader.entry_reader().await.expect("failed to read central directory").is_none());
+}
+
+#[tokio::test]
+async fn entry_reader_read_vectored() {
+    use crate::read::seek::ZipFileReader;
+    use std::io::IoSliceMut;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    let opts = EntryOptions::new("foo.bar".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, b"Hello, world!").await.expect("failed to write entry");
+    zip_writer.close().await.expect("failed to close writer");
+
+    let mut zip_reader =
+        ZipFileReader::new(Cursor::new(input_stream.into_inner())).await.expect("failed to open reader");
+    let mut entry_reader = zip_reader.entry_reader(0).await.expect("failed to open entry reader");
+
+    let mut first = [0u8; 5];
+    let mut second = [0u8; 16];
+    let mut bufs = [IoSliceMut::new(&mut first), IoSliceMut::new(&mut second)];
+
+    let read = std::future::poll_fn(|c| std::pin::Pin::new(&mut entry_reader).poll_read_vectored(c, &mut bufs))
+        .await
+        .expect("failed to read entry");
+    assert_eq!(13, read);
+    assert_eq!(b"Hello", &first);
+    assert_eq!(b", world!", &second[..8]);
+
+    let mut bufs = [IoSliceMut::new(&mut first)];
+    let read = std::future::poll_fn(|c| std::pin::Pin::new(&mut entry_reader).poll_read_vectored(c, &mut bufs))
+        .await
+        .expect("failed to read entry");
+    assert_eq!(0, read);
+    assert!(entry_reader.compare_crc());
+}
+
+#[tokio::test]
+async fn unsupported_version_needed() {
+    use crate::error::ZipError;
+    use crate::read::seek::ZipFileReader;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    for name in ["aes.txt", "future.txt", "lzma.txt"] {
+        let opts = EntryOptions::new(name.to_string(), Compression::Stored);
+        zip_writer.write_entry_whole(opts, b"data").await.expect("failed to write entry");
+    }
+
+    // Forge the versions needed to extract within the central directory only.
+    zip_writer.cd_entries[0].header.v_needed = 51;
+    zip_writer.cd_entries[1].header.v_needed = 70;
+    zip_writer.cd_entries[2].header.v_needed = 63;
+    zip_writer.close().await.expect("failed to close writer");
+
+    let mut zip_reader = ZipFileReader::new(input_stream).await.expect("failed to open reader");
+    assert_eq!(51, zip_reader.entries()[0].version_needed());
+
+    let result = zip_reader.entry_reader(0).await;
+    assert!(matches!(result, Err(ZipError::FeatureNotSupported(feature)) if feature.contains("5.1")));
+    let result = zip_reader.entry_reader(1).await;
+    assert!(matches!(result, Err(ZipError::FeatureNotSupported(_))));
+
+    let entry_reader = zip_reader.entry_reader(2).await.expect("failed to open entry reader");
+    assert_eq!("data", entry_reader.read_to_string_crc().await.expect("failed to read entry"));
+}
+
+#[tokio::test]
+async fn extract_single_entry() {
+    use crate::error::ZipError;
+    use crate::read::seek::ZipFileReader;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    let opts = EntryOptions::new("bundle/config.json".to_string(), Compression::Stored).unix_permissions(0o640);
+    zip_writer.write_entry_whole(opts, b"{}").await.expect("failed to write entry");
+    let opts = EntryOptions::new("../escape.json".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, b"{}").await.expect("failed to write entry");
+    zip_writer.close().await.expect("failed to close writer");
+
+    let directory = std::env::temp_dir().join(format!("async_zip_extract_single_entry_{}", std::process::id()));
+    let dst = directory.join("nested").join("config.json");
+
+    let mut zip_reader = ZipFileReader::new(input_stream).await.expect("failed to open reader");
+    zip_reader.extract_entry_to("bundle/config.json", &dst).await.expect("failed to extract entry");
+
+    assert_eq!(b"{}", &tokio::fs::read(&dst).await.expect("failed to read extracted file")[..]);
+
+    let metadata = tokio::fs::metadata(&dst).await.expect("failed to read metadata");
+    let expected = std::time::SystemTime::from(*zip_reader.entries()[0].last_modified());
+    assert_eq!(expected, metadata.modified().expect("failed to read modification time"));
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(0o640, metadata.permissions().mode() & 0o777);
+    }
+
+    let result = zip_reader.extract_entry_to("missing.json", &dst).await;
+    assert!(matches!(result, Err(ZipError::EntryNotFound(name)) if name == "missing.json"));
+    let result = zip_reader.extract_entry_to("../escape.json", &directory.join("escape.json")).await;
+    assert!(matches!(result, Err(ZipError::UnsafePath(_))));
+
+    tokio::fs::remove_dir_all(&directory).await.expect("failed to remove temporary directory");
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn extract_symlink_policies() {
+    use crate::error::ZipError;
+    use crate::read::seek::ZipFileReader;
+    use crate::read::{ExtractOptions, SymlinkPolicy};
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    let opts = EntryOptions::new("data/target.txt".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, b"target").await.expect("failed to write entry");
+    let opts = EntryOptions::new("data/inside".to_string(), Compression::Stored).unix_permissions(0o120777);
+    zip_writer.write_entry_whole(opts, b"target.txt").await.expect("failed to write entry");
+    let opts = EntryOptions::new("data/outside".to_string(), Compression::Stored).unix_permissions(0o120777);
+    zip_writer.write_entry_whole(opts, b"../../escaped.txt").await.expect("failed to write entry");
+    zip_writer.close().await.expect("failed to close writer");
+
+    let archive = input_stream.into_inner();
+    let base = std::env::temp_dir().join(format!("async_zip_extract_symlink_policies_{}", std::process::id()));
+
+    let policies =
+        [SymlinkPolicy::Recreate, SymlinkPolicy::SkipExternal, SymlinkPolicy::RejectAll, SymlinkPolicy::AsRegularFile];
+
+    for (index, policy) in policies.into_iter().enumerate() {
+        let directory = base.join(index.to_string());
+        let mut zip_reader = ZipFileReader::new(Cursor::new(archive.clone())).await.expect("failed to open reader");
+        assert!(zip_reader.entries()[1].is_symlink());
+
+        let options = ExtractOptions::new().symlink_policy(policy);
+        let result = zip_reader.extract_to_with_options(&directory, options).await;
+
+        let inside = tokio::fs::symlink_metadata(directory.join("data/inside")).await;
+        let outside = tokio::fs::symlink_metadata(directory.join("data/outside")).await;
+
+        match policy {
+            SymlinkPolicy::Recreate => {
+                result.expect("failed to extract");
+                assert!(inside.expect("missing inside link").file_type().is_symlink());
+                assert!(outside.expect("missing outside link").file_type().is_symlink());
+                let target = tokio::fs::read_link(directory.join("data/outside")).await.expect("failed to read link");
+                assert_eq!(std::path::Path::new("../../escaped.txt"), target);
+            }
+            SymlinkPolicy::SkipExternal => {
+                result.expect("failed to extract");
+                assert!(inside.expect("missing inside link").file_type().is_symlink());
+                assert!(outside.is_err());
+                let content = tokio::fs::read(directory.join("data/inside")).await.expect("failed to follow link");
+                assert_eq!(b"target", &content[..]);
+            }
+            SymlinkPolicy::RejectAll => {
+                assert!(matches!(result, Err(ZipError::SymlinkRejected(name)) if name == "data/inside"));
+                assert!(inside.is_err());
+                assert!(outside.is_err());
+            }
+            SymlinkPolicy::AsRegularFile => {
+                result.expect("failed to extract");
+                assert!(inside.expect("missing inside file").file_type().is_file());
+                let content = tokio::fs::read(directory.join("data/outside")).await.expect("failed to read file");
+                assert_eq!(b"../../escaped.txt", &content[..]);
+            }
+        }
+    }
+
+    // A chain of links which each lexically resolve within the destination, but on disk point outside of it.
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    for (name, target) in [("sub/up", ".."), ("sub/above", "up/.."), ("sub/up/esc", "..")] {
+        let opts = EntryOptions::new(name.to_string(), Compression::Stored).unix_permissions(0o120777);
+        zip_writer.write_entry_whole(opts, target.as_bytes()).await.expect("failed to write entry");
+    }
+    let opts = EntryOptions::new("sub/up/esc/pwned".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, b"pwned").await.expect("failed to write entry");
+    zip_writer.close().await.expect("failed to close writer");
+
+    let root = base.join("chained");
+    let directory = root.join("dst");
+    let mut zip_reader = ZipFileReader::new(Cursor::new(input_stream.into_inner())).await.expect("failed to open");
+
+    let result = zip_reader.extract_to(&directory).await;
+    assert!(matches!(result, Err(ZipError::UnsafePath(name)) if name == "sub/up/esc"));
+
+    // The in-bounds link is created, whereas the link which passes through it back out of the destination isn't.
+    let up = tokio::fs::symlink_metadata(directory.join("sub/up")).await.expect("missing link");
+    assert!(up.file_type().is_symlink());
+    assert!(tokio::fs::symlink_metadata(directory.join("sub/above")).await.is_err());
+
+    // Nothing was written outside of the destination.
+    let mut outside = tokio::fs::read_dir(&root).await.expect("failed to read directory");
+    while let Some(entry) = outside.next_entry().await.expect("failed to read directory") {
+        assert_eq!("dst", entry.file_name());
+    }
+
+    tokio::fs::remove_dir_all(&base).await.expect("failed to remove temporary directory");
+}
+
+// A source which records the byte ranges read from it.
+struct RecordingSource {
+    inner: Cursor<Vec<u8>>,
+    reads: Vec<std::ops::Range<u64>>,
+}
+
+impl tokio::io::AsyncRead for RecordingSource {
+    fn poll_read(
+        mut self: std::pin::Pin<&mut Self>,
+        c: &mut std::task::Context<'_>,
+        b: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        let start = self.inner.position();
+        let prev_len = b.filled().len();
+        let poll = tokio::io::AsyncRead::poll_read(std::pin::Pin::new(&mut self.inner), c, b);
+
+        let read = (b.filled().len() - prev_len) as u64;
+        if read > 0 {
+            self.reads.push(start..start + read);
+        }
+
+        poll
+    }
+}
+
+impl tokio::io::AsyncSeek for RecordingSource {
+    fn start_seek(mut self: std::pin::Pin<&mut Self>, position: std::io::SeekFrom) -> std::io::Result<()> {
+        tokio::io::AsyncSeek::start_seek(std::pin::Pin::new(&mut self.inner), position)
+    }
+
+    fn poll_complete(
+        mut self: std::pin::Pin<&mut Self>,
+        c: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<u64>> {
+        tokio::io::AsyncSeek::poll_complete(std::pin::Pin::new(&mut self.inner), c)
+    }
+}
+
+#[tokio::test]
+async fn listing_reads_only_footer() {
+    use crate::read::seek::ZipFileReader;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    for name in ["foo.bar", "bar.foo"] {
+        let opts = EntryOptions::new(name.to_string(), Compression::Stored);
+        zip_writer.write_entry_whole(opts, &vec![0xAA; 100_000]).await.expect("failed to write entry");
+    }
+    zip_writer.close().await.expect("failed to close writer");
+
+    let archive = input_stream.into_inner();
+    let length = archive.len();
+    let cent_dir_offset = u32::from_le_bytes(archive[length - 6..length - 2].try_into().unwrap()) as u64;
+
+    let source = RecordingSource { inner: Cursor::new(archive), reads: Vec::new() };
+    let zip_reader = ZipFileReader::new(source).await.expect("failed to open reader");
+    assert_eq!(2, zip_reader.entries().len());
+
+    // Every read falls within the central directory or the end of central directory header.
+    assert!(!zip_reader.reader.reads.is_empty());
+    for range in &zip_reader.reader.reads {
+        assert!(range.start >= cent_dir_offset, "read of {:?} touched entry data", range);
+    }
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn empty_entry_stored() {
+    use crate::read::seek::ZipFileReader;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    let opts = EntryOptions::new("empty.txt".to_string(), Compression::Deflate);
+    zip_writer.write_entry_whole(opts, &[]).await.expect("failed to write entry");
+    zip_writer.close().await.expect("failed to close writer");
+
+    input_stream.set_position(0);
+    let mut zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+
+    let entry = &zip_reader.entries()[0];
+    assert_eq!(&Compression::Stored, entry.compression());
+    assert_eq!(Some(0), entry.compressed_size());
+    assert_eq!(Some(0), entry.crc32());
+    assert_eq!(10, entry.version_needed());
+
+    let entry_reader = zip_reader.entry_reader(0).await.expect("failed to open entry reader");
+    assert!(entry_reader.read_to_end_crc().await.expect("failed to read entry").is_empty());
+}
+
+#[tokio::test]
+async fn wrapped_entry_count() {
+    use crate::read::mem::ZipFileReader;
+    use crate::read::ReaderOptions;
+
+    const ENTRIES: usize = u16::MAX as usize + 2;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    // The writer doesn't support ZIP64, so the entry count stored within the EOCDR wraps around to one.
+    for index in 0..ENTRIES {
+        let opts = EntryOptions::new(index.to_string(), Compression::Stored);
+        zip_writer.write_entry_whole(opts, &[]).await.expect("failed to write entry");
+    }
+    zip_writer.close().await.expect("failed to close writer");
+
+    let archive = input_stream.into_inner();
+
+    let zip_reader = ZipFileReader::new(&archive).await.expect("failed to open reader");
+    assert_eq!(1, zip_reader.entries().len());
+
+    let options = ReaderOptions::new().scan_central_directory(true);
+    let zip_reader = ZipFileReader::with_options(&archive, options).await.expect("failed to open reader");
+    assert_eq!(ENTRIES, zip_reader.entries().len());
+    assert_eq!("65536", zip_reader.entries()[ENTRIES - 1].name());
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn read_chunk_channel() {
+    use crate::read::seek::ZipFileReader;
+
+    let data =
+        "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt...".repeat(16);
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+    let opts = EntryOptions::new("foo.bar".to_string(), Compression::Deflate);
+    zip_writer.write_entry_whole(opts, data.as_bytes()).await.expect("failed to write entry");
+    zip_writer.close().await.expect("failed to close writer");
+
+    input_stream.set_position(0);
+    let mut zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+    let mut entry_reader = zip_reader.entry_reader(0).await.expect("failed to open entry reader");
+
+    let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+
+    let producer = async move {
+        while let Some(chunk) = entry_reader.read_chunk(64).await.expect("failed to read chunk") {
+            assert!(chunk.len() <= 64);
+            sender.send(chunk).await.expect("failed to send chunk");
+        }
+        assert!(entry_reader.read_chunk(64).await.expect("failed to read chunk").is_none());
+    };
+
+    let consumer = async move {
+        let mut received = Vec::new();
+        while let Some(chunk) = receiver.recv().await {
+            received.extend_from_slice(&chunk);
+        }
+        received
+    };
+
+    let ((), received) = tokio::join!(producer, consumer);
+    assert_eq!(data.as_bytes(), &received[..]);
+}
+
+// A writer of values in least-significant-bit-first order, for hand-crafting legacy compressed bitstreams.
+#[cfg(feature = "legacy")]
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    bits: usize,
+}
+
+#[cfg(feature = "legacy")]
+impl BitWriter {
+    fn write(&mut self, value: u16, width: u8) {
+        for offset in 0..width {
+            if self.bits % 8 == 0 {
+                self.bytes.push(0);
+            }
+            *self.bytes.last_mut().unwrap() |= (((value >> offset) & 1) as u8) << (self.bits % 8);
+            self.bits += 1;
+        }
+    }
+}
+
+// Write a stored entry, then rewrite its central directory header to claim that the data was compressed.
+#[cfg(feature = "legacy")]
+async fn legacy_archive(method: u16, compressed: &[u8], uncompressed: &[u8]) -> Vec<u8> {
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    let opts = EntryOptions::new("legacy.txt".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, compressed).await.expect("failed to write entry");
+
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(uncompressed);
+
+    let header = &mut zip_writer.cd_entries[0].header;
+    header.compression = method;
+    header.uncompressed_size = uncompressed.len() as u32;
+    header.crc = hasher.finalize();
+
+    zip_writer.close().await.expect("failed to close writer");
+    input_stream.into_inner()
+}
+
+#[cfg(feature = "legacy")]
+#[tokio::test]
+async fn legacy_shrink() {
+    use crate::read::mem::ZipFileReader;
+
+    let expected = b"ABABABA ABABABA ABABABA";
+
+    // The LZW codes for the expected data (259 is used before it's defined), with a code size increase partway.
+    let codes: [u16; 11] = [65, 66, 257, 259, 32, 259, 258, 258, 261, 263, 259];
+    let mut writer = BitWriter::default();
+    for (index, code) in codes.into_iter().enumerate() {
+        let width = if index < 4 { 9 } else { 10 };
+        if index == 4 {
+            writer.write(256, 9);
+            writer.write(1, 9);
+        }
+        writer.write(code, width);
+    }
+
+    let archive = legacy_archive(1, &writer.bytes, expected).await;
+    let mut zip_reader = ZipFileReader::new(&archive).await.expect("failed to open reader");
+    assert_eq!(&Compression::Shrink, zip_reader.entries()[0].compression());
+
+    let entry_reader = zip_reader.entry_reader(0).await.expect("failed to open entry reader");
+    assert_eq!(&expected[..], &entry_reader.read_to_end_crc().await.expect("failed to read entry")[..]);
+}
+
+#[cfg(feature = "legacy")]
+#[tokio::test]
+async fn legacy_reduce() {
+    use crate::read::mem::ZipFileReader;
+
+    const DLE: u8 = 144;
+
+    for factor in 1..=4u8 {
+        let mask = 0xFF >> factor;
+
+        // The inner layer: "abc", a copy of 12 bytes from 3 bytes back, a literal DLE, "xyz", then a copy of (mask +
+        // 8) bytes from 4 bytes back which requires an additional length byte.
+        let inner = [b'a', b'b', b'c', DLE, 9, 2, DLE, 0, b'x', b'y', b'z', DLE, mask, 5, 3];
+
+        let mut expected = b"abcabcabcabcabc".to_vec();
+        expected.push(DLE);
+        expected.extend_from_slice(b"xyz");
+        for _ in 0..mask as usize + 8 {
+            expected.push(expected[expected.len() - 4]);
+        }
+
+        let mut follower_sets = vec![Vec::new(); 256];
+        follower_sets[b'a' as usize] = vec![b'b'];
+        follower_sets[b'b' as usize] = vec![b'c'];
+        follower_sets[DLE as usize] = vec![0, 9, mask];
+
+        let mut writer = BitWriter::default();
+        for set in follower_sets.iter().rev() {
+            writer.write(set.len() as u16, 6);
+            for byte in set {
+                writer.write(*byte as u16, 8);
+            }
+        }
+
+        // The outer layer: each byte is either an index into its predecessor's follower set or a flagged literal.
+        let mut last = 0;
+        for byte in inner {
+            let set = &follower_sets[last as usize];
+            match set.iter().position(|follower| *follower == byte) {
+                Some(index) => {
+                    writer.write(0, 1);
+                    writer.write(index as u16, if set.len() > 2 { 2 } else { 1 });
+                }
+                None if set.is_empty() => writer.write(byte as u16, 8),
+                None => {
+                    writer.write(1, 1);
+                    writer.write(byte as u16, 8);
+                }
+            }
+            last = byte;
+        }
+
+        let archive = legacy_archive(1 + factor as u16, &writer.bytes, &expected).await;
+        let mut zip_reader = ZipFileReader::new(&archive).await.expect("failed to open reader");
+        assert_eq!(&Compression::Reduce(factor), zip_reader.entries()[0].compression());
+
+        let entry_reader = zip_reader.entry_reader(0).await.expect("failed to open entry reader");
+        assert_eq!(expected, entry_reader.read_to_end_crc().await.expect("failed to read entry"));
+    }
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+    let opts = EntryOptions::new("legacy.txt".to_string(), Compression::Reduce(1));
+    assert!(zip_writer.write_entry_whole(opts, b"data").await.is_err());
+}
+
+#[tokio::test]
+async fn skip_comment_decoding() {
+    use crate::read::mem::ZipFileReader;
+    use crate::read::ReaderOptions;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    let opts = EntryOptions::new("foo.bar".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, b"data").await.expect("failed to write entry");
+    zip_writer.comment("hello".to_string());
+    zip_writer.close().await.expect("failed to close writer");
+
+    // Replace the ending comment with bytes which aren't valid UTF-8.
+    let mut archive = input_stream.into_inner();
+    let length = archive.len();
+    archive[length - 5..].copy_from_slice(&[0xFF, 0xFE, 0xFD, 0xFC, 0xFB]);
+
+    assert!(ZipFileReader::new(&archive).await.is_err());
+
+    let options = ReaderOptions::new().skip_comment_decoding(true);
+    let zip_reader = ZipFileReader::with_options(&archive, options).await.expect("failed to open reader");
+
+    assert_eq!(None, zip_reader.comment());
+    assert_eq!(Some(&[0xFF, 0xFE, 0xFD, 0xFC, 0xFB][..]), zip_reader.comment_bytes());
+    assert_eq!("foo.bar", zip_reader.entries()[0].name());
+}
+
+#[tokio::test]
+async fn written_local_header_offsets() {
+    use crate::read::seek::ZipFileReader;
+    use tokio::io::AsyncWriteExt;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+    let mut offsets = Vec::new();
+
+    let opts = EntryOptions::new("foo.bar".to_string(), Compression::Stored);
+    offsets.push(zip_writer.write_entry_whole(opts, b"foo").await.expect("failed to write entry"));
+
+    let opts = EntryOptions::new("bar.foo".to_string(), Compression::Stored);
+    let mut entry_writer = zip_writer.write_entry_stream(opts).await.expect("failed to open write entry");
+    entry_writer.write_all(b"bar").await.expect("failed to write entry");
+    offsets.push(entry_writer.close().await.expect("failed to close entry"));
+
+    let opts = EntryOptions::new("baz.foo".to_string(), Compression::Stored);
+    offsets.push(zip_writer.write_entry_whole(opts, b"baz").await.expect("failed to write entry"));
+    zip_writer.close().await.expect("failed to close writer");
+
+    assert_eq!(0, offsets[0]);
+
+    input_stream.set_position(0);
+    let zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+    let reported: Vec<_> = zip_reader.entries().iter().map(|entry| entry.local_header_offset()).collect();
+    assert_eq!(offsets.into_iter().map(Some).collect::<Vec<_>>(), reported);
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn deflate_options_from_flags() {
+    use crate::read::seek::ZipFileReader;
+    use crate::spec::compression::DeflateOption;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    for name in ["normal", "maximum", "fast", "superfast"] {
+        let opts = EntryOptions::new(name.to_string(), Compression::Deflate);
+        zip_writer.write_entry_whole(opts, b"foo bar").await.expect("failed to write entry");
+    }
+
+    let opts = EntryOptions::new("stored".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, b"foo bar").await.expect("failed to write entry");
+
+    for (index, bits) in [0u8, 1, 2, 3, 3].into_iter().enumerate() {
+        zip_writer.cd_entries[index].header.flags.compression_options = bits;
+    }
+    zip_writer.close().await.expect("failed to close writer");
+
+    input_stream.set_position(0);
+    let zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+    let options: Vec<_> = zip_reader.entries().iter().map(|entry| entry.deflate_option()).collect();
+
+    assert_eq!(
+        vec![
+            Some(DeflateOption::Normal),
+            Some(DeflateOption::Maximum),
+            Some(DeflateOption::Fast),
+            Some(DeflateOption::SuperFast),
+            None
+        ],
+        options
+    );
+}
+
+#[tokio::test]
+async fn validate_structure_warnings() {
+    use crate::read::mem::ZipFileReader;
+    use crate::read::StructureWarning;
+
+    async fn archive(overlap: bool) -> Vec<u8> {
+        let mut output = Cursor::new(Vec::<u8>::new());
+        let mut zip_writer = ZipFileWriter::new(&mut output);
+
+        for name in ["foo.bar", "bar.foo", "baz.foo"] {
+            let opts = EntryOptions::new(name.to_string(), Compression::Stored);
+            zip_writer.write_entry_whole(opts, b"foo bar baz").await.expect("failed to write entry");
+        }
+
+        if overlap {
+            zip_writer.cd_entries[2].header.lh_offset = zip_writer.cd_entries[1].header.lh_offset + 4;
+        }
+
+        zip_writer.close().await.expect("failed to close writer");
+        output.into_inner()
+    }
+
+    let data = archive(false).await;
+    let zip_reader = ZipFileReader::new(&data).await.expect("failed to open reader");
+    assert_eq!(Ok(()), zip_reader.validate_structure());
+
+    let data = archive(true).await;
+    let zip_reader = ZipFileReader::new(&data).await.expect("failed to open reader");
+    let expected = vec![StructureWarning::OverlappingEntries { first: 1, second: 2 }];
+    assert_eq!(Err(expected), zip_reader.validate_structure());
+
+    // Inflate the central directory size recorded within the end of central directory record.
+    let mut data = archive(false).await;
+    let size_offset = data.len() - 22 + 12;
+    let actual = u32::from_le_bytes(data[size_offset..size_offset + 4].try_into().unwrap());
+    data[size_offset..size_offset + 4].copy_from_slice(&(actual + 8).to_le_bytes());
+
+    let zip_reader = ZipFileReader::new(&data).await.expect("failed to open reader");
+    let warnings = zip_reader.validate_structure().expect_err("expected structural warnings");
+    let (recorded, actual) = (actual as u64 + 8, actual as u64);
+    assert!(warnings.contains(&StructureWarning::CentralDirectorySizeMismatch { recorded, actual }));
+}
+
+#[tokio::test]
+async fn caching_source_refetches_nothing() {
+    use crate::read::cache::CachingSource;
+    use crate::read::seek::ZipFileReader;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+    let data: Vec<u8> = (0..20_000u32).map(|value| value as u8).collect();
+
+    for name in ["foo.bar", "bar.foo"] {
+        let opts = EntryOptions::new(name.to_string(), Compression::Stored);
+        zip_writer.write_entry_whole(opts, &data).await.expect("failed to write entry");
+    }
+    zip_writer.close().await.expect("failed to close writer");
+
+    let source = RecordingSource { inner: Cursor::new(input_stream.into_inner()), reads: Vec::new() };
+    let source = CachingSource::with_block_size(source, 16, 4096);
+    let mut zip_reader = ZipFileReader::new(source).await.expect("failed to open reader");
+
+    let entry_reader = zip_reader.entry_reader(1).await.expect("failed to open entry reader");
+    assert_eq!(data, entry_reader.read_to_end_crc().await.expect("failed to read entry"));
+
+    let misses = zip_reader.reader.stats().misses;
+    zip_reader.reader.get_mut().reads.clear();
+
+    let entry_reader = zip_reader.entry_reader(1).await.expect("failed to open entry reader");
+    assert_eq!(data, entry_reader.read_to_end_crc().await.expect("failed to read entry"));
+
+    assert!(zip_reader.reader.get_mut().reads.is_empty());
+    assert_eq!(misses, zip_reader.reader.stats().misses);
+    assert!(zip_reader.reader.stats().hits > 0);
+}
+
+#[tokio::test]
+async fn compression_info_method_ids() {
+    use crate::read::seek::ZipFileReader;
+
+    let mut methods = vec![(Compression::Stored, 0)];
+    #[cfg(feature = "deflate")]
+    methods.push((Compression::Deflate, 8));
+    #[cfg(feature = "bzip2")]
+    methods.push((Compression::Bz, 12));
+    #[cfg(feature = "zstd")]
+    methods.push((Compression::Zstd, 93));
+    #[cfg(feature = "xz")]
+    methods.push((Compression::Xz, 95));
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    for (index, (compression, _)) in methods.iter().enumerate() {
+        let opts = EntryOptions::new(format!("{}.bin", index), *compression);
+        zip_writer.write_entry_whole(opts, b"foo bar baz").await.expect("failed to write entry");
+    }
+    zip_writer.close().await.expect("failed to close writer");
+
+    input_stream.set_position(0);
+    let zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+    let info: Vec<_> = zip_reader.entries().iter().map(|entry| entry.compression_info()).collect();
+
+    assert_eq!(methods, info);
+}
+
+#[tokio::test]
+async fn extraction_plan_rejects_unsafe_paths() {
+    use crate::read::seek::ZipFileReader;
+    use crate::read::{ExtractOptions, SymlinkPolicy};
+    use std::path::Path;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    let opts = EntryOptions::new("data/".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, &[]).await.expect("failed to write entry");
+    let opts = EntryOptions::new("data/foo.txt".to_string(), Compression::Stored).unix_permissions(0o100644);
+    zip_writer.write_entry_whole(opts, b"foo bar").await.expect("failed to write entry");
+    let opts = EntryOptions::new("../escaped.txt".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, b"escaped").await.expect("failed to write entry");
+    let opts = EntryOptions::new("data/link".to_string(), Compression::Stored).unix_permissions(0o120777);
+    zip_writer.write_entry_whole(opts, b"foo.txt").await.expect("failed to write entry");
+    zip_writer.close().await.expect("failed to close writer");
+
+    input_stream.set_position(0);
+    let mut zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+    let dst = Path::new("output");
+
+    let plan = zip_reader.extraction_plan(dst).await.expect("failed to plan extraction");
+    assert_eq!(&[dst.join("data/foo.txt")], plan.files());
+    assert_eq!(&[dst.join("data")], plan.directories());
+    assert_eq!(&[dst.join("data/link")], plan.symlinks());
+    assert_eq!(&[(dst.join("data/foo.txt"), 0o644)], plan.permissions());
+    assert_eq!(&["../escaped.txt".to_string()], plan.rejected());
+    assert_eq!(7, plan.total_bytes());
+
+    let options = ExtractOptions::new().symlink_policy(SymlinkPolicy::RejectAll);
+    let plan = zip_reader.extraction_plan_with_options(dst, options).await.expect("failed to plan extraction");
+    assert!(plan.symlinks().is_empty());
+    assert_eq!(&["../escaped.txt".to_string(), "data/link".to_string()], plan.rejected());
+}
+
+#[tokio::test]
+async fn extraction_plan_resolves_symlinks() {
+    use crate::read::seek::ZipFileReader;
+    use crate::read::{ExtractOptions, SymlinkPolicy};
+    use std::path::Path;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    let opts = EntryOptions::new("data/foo.txt".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, b"foo bar").await.expect("failed to write entry");
+    let entries: [(&str, &[u8]); 4] = [
+        ("data/link", b"foo.txt"),
+        ("data/escape", b"../../etc"),
+        ("data/self", b"."),
+        ("data/dangling", b"missing/.."),
+    ];
+    for (name, target) in entries {
+        let opts = EntryOptions::new(name.to_string(), Compression::Stored).unix_permissions(0o120777);
+        zip_writer.write_entry_whole(opts, target).await.expect("failed to write entry");
+    }
+    // This entry would be written through the recreated link to the data directory.
+    let opts = EntryOptions::new("data/self/bar.txt".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, b"bar").await.expect("failed to write entry");
+    zip_writer.close().await.expect("failed to close writer");
+
+    input_stream.set_position(0);
+    let mut zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+    let dst = Path::new("output");
+
+    let plan = zip_reader.extraction_plan(dst).await.expect("failed to plan extraction");
+    assert_eq!(&[dst.join("data/link"), dst.join("data/self")], plan.symlinks());
+    assert_eq!(&[dst.join("data/escape"), dst.join("data/dangling")], plan.skipped_symlinks());
+    assert_eq!(&["data/self/bar.txt".to_string()], plan.rejected());
+    assert_eq!(&[dst.join("data/foo.txt")], plan.files());
+
+    let options = ExtractOptions::new().symlink_policy(SymlinkPolicy::Recreate);
+    let plan = zip_reader.extraction_plan_with_options(dst, options).await.expect("failed to plan extraction");
+    assert_eq!(4, plan.symlinks().len());
+    assert!(plan.skipped_symlinks().is_empty());
+    assert_eq!(&["data/self/bar.txt".to_string()], plan.rejected());
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn signatureless_data_descriptor_stream() {
+    use crate::read::stream::ZipFileReader;
+    use tokio::io::AsyncWriteExt;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    // The stored entry's data contains a local file header signature which mustn't be mistaken for the boundary.
+    let entries = [
+        ("foo.bar", Compression::Deflate, b"Lorem ipsum dolor sit amet, consectetur adipiscing elit...".to_vec()),
+        ("bar.foo", Compression::Stored, b"foo PK\x03\x04 bar PK\x01\x02 baz".to_vec()),
+        ("baz.foo", Compression::Deflate, b"sed do eiusmod tempor incididunt ut labore et dolore...".to_vec()),
+    ];
+
+    for (name, compression, data) in &entries {
+        let opts = EntryOptions::new(name.to_string(), *compression);
+        let mut entry_writer = zip_writer.write_entry_stream(opts).await.expect("failed to open write entry");
+        entry_writer.write_all(data).await.expect("failed to write entry");
+        entry_writer.close().await.expect("failed to close entry");
+    }
+    zip_writer.close().await.expect("failed to close writer");
+
+    // Strip each data descriptor's optional signature, as some producers do.
+    let signature = crate::spec::signature::DATA_DESCRIPTOR.to_le_bytes();
+    let mut archive = input_stream.into_inner();
+    while let Some(index) = archive.windows(4).position(|window| window == signature) {
+        archive.drain(index..index + 4);
+    }
+
+    let mut source = Cursor::new(archive);
+    let mut zip_reader = ZipFileReader::new(&mut source);
+
+    for (name, _, data) in &entries {
+        let entry_reader = zip_reader.entry_reader().await.expect("failed to open entry reader").unwrap();
+        assert_eq!(*name, entry_reader.entry().name());
+
+        let buffer = entry_reader.read_to_end_crc().await.expect("failed to read entry");
+        assert_eq!(data, &buffer);
+    }
+
+    assert!(zip_reader.entry_reader().await.expect("failed to open entry reader").is_none());
+    assert!(zip_reader.finished());
+}
+
+#[tokio::test]
+async fn zip64_signatureless_data_descriptor_stream() {
+    use crate::error::ZipError;
+    use crate::read::stream::ZipFileReader;
+    use crate::spec::signature::{CENTRAL_DIRECTORY_FILE_HEADER, DATA_DESCRIPTOR, LOCAL_FILE_HEADER};
+
+    // A stored entry which defers its CRC32 value and sizes to a ZIP64 data descriptor, with or without its signature.
+    fn entry(name: &str, data: &[u8], signed: bool, uncompressed: u64) -> Vec<u8> {
+        let mut record = LOCAL_FILE_HEADER.to_le_bytes().to_vec();
+        for field in [20u16, 0x0008, 0, 0, 0x21] {
+            record.extend_from_slice(&field.to_le_bytes());
+        }
+        record.extend_from_slice(&[0; 12]);
+        record.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        record.extend_from_slice(&0u16.to_le_bytes());
+        record.extend_from_slice(name.as_bytes());
+        record.extend_from_slice(data);
+
+        if signed {
+            record.extend_from_slice(&DATA_DESCRIPTOR.to_le_bytes());
+        }
+        record.extend_from_slice(&crc32fast::hash(data).to_le_bytes());
+        record.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        record.extend_from_slice(&uncompressed.to_le_bytes());
+        record
+    }
+
+    let mut archive = entry("foo.bar", b"Hello, world!", false, 13);
+    archive.extend(entry("bar.foo", b"Goodbye, world!", true, 15));
+    archive.extend(entry("baz.foo", b"Misreported", false, 99));
+    archive.extend_from_slice(&CENTRAL_DIRECTORY_FILE_HEADER.to_le_bytes());
+
+    let mut zip_reader = ZipFileReader::new(Cursor::new(archive));
+
+    for (name, data) in [("foo.bar", &b"Hello, world!"[..]), ("bar.foo", &b"Goodbye, world!"[..])] {
+        let entry_reader = zip_reader.entry_reader().await.expect("failed to open entry reader").unwrap();
+        assert_eq!(name, entry_reader.entry().name());
+        assert_eq!(data, &entry_reader.read_to_end_crc().await.expect("failed to read entry")[..]);
+    }
+
+    // The boundary is found via the compressed size, but the uncompressed size must also match the data.
+    let entry_reader = zip_reader.entry_reader().await.expect("failed to open entry reader").unwrap();
+    assert!(matches!(entry_reader.read_to_end_crc().await, Err(ZipError::InvalidArchive(_))));
+}
+
+#[tokio::test]
+async fn version_made_by_host_round_trip() {
+    use crate::read::seek::ZipFileReader;
+    use crate::spec::version::HostSystem;
+    use tokio::io::AsyncWriteExt;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    let opts = EntryOptions::new("foo.bar".to_string(), Compression::Stored).unix_permissions(0o100644);
+    zip_writer.write_entry_whole(opts, b"foo").await.expect("failed to write entry");
+
+    zip_writer.set_version_made_by(HostSystem::WindowsNtfs, 45);
+    let opts = EntryOptions::new("bar.foo".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, b"bar").await.expect("failed to write entry");
+
+    zip_writer.set_version_made_by(HostSystem::Darwin, 20);
+    let opts = EntryOptions::new("baz.foo".to_string(), Compression::Stored);
+    let mut entry_writer = zip_writer.write_entry_stream(opts).await.expect("failed to open write entry");
+    entry_writer.write_all(b"baz").await.expect("failed to write entry");
+    entry_writer.close().await.expect("failed to close entry");
+    zip_writer.close().await.expect("failed to close writer");
+
+    input_stream.set_position(0);
+    let zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+    let hosts: Vec<_> = zip_reader.entries().iter().map(|entry| entry.host_system()).collect();
+
+    assert_eq!(vec![Some(HostSystem::Unix), Some(HostSystem::WindowsNtfs), Some(HostSystem::Darwin)], hosts);
+    assert_eq!(Some(0o644), zip_reader.entries()[0].unix_permissions());
+    assert_eq!(HostSystem::Unknown(42), HostSystem::from_u8(42));
+}
+
+#[tokio::test]
+async fn info_zip_unicode_path_check_crc() {
+    use crate::read::seek::ZipFileReader;
+
+    fn unicode_field(original: &str, unicode: &str) -> Vec<u8> {
+        let mut data = vec![1];
+        data.extend_from_slice(&crc32fast::hash(original.as_bytes()).to_le_bytes());
+        data.extend_from_slice(unicode.as_bytes());
+        data
+    }
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    let opts = EntryOptions::new("foo.bar".to_string(), Compression::Stored)
+        .extra_field(0x7075, unicode_field("foo.bar", "fö.bar"))
+        .comment("comment".to_string())
+        .extra_field(0x6375, unicode_field("comment", "cömment"));
+    zip_writer.write_entry_whole(opts, b"foo").await.expect("failed to write entry");
+
+    // The check CRC was computed over a name other than the one which was written, so the record is stale.
+    let opts = EntryOptions::new("renamed.bar".to_string(), Compression::Stored)
+        .extra_field(0x7075, unicode_field("bar.foo", "bär.foo"))
+        .comment("comment".to_string())
+        .extra_field(0x6375, unicode_field("original", "cömment"));
+    zip_writer.write_entry_whole(opts, b"bar").await.expect("failed to write entry");
+    zip_writer.close().await.expect("failed to close writer");
+
+    input_stream.set_position(0);
+    let zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+
+    assert_eq!("fö.bar", zip_reader.entries()[0].name());
+    assert_eq!(Some("cömment"), zip_reader.entries()[0].comment());
+    assert_eq!("renamed.bar", zip_reader.entries()[1].name());
+    assert_eq!(Some("comment"), zip_reader.entries()[1].comment());
+
+    // The record within each local file header is checked in the same way when read from a non-seekable source.
+    input_stream.set_position(0);
+    let mut zip_reader = crate::read::stream::ZipFileReader::new(&mut input_stream);
+
+    for expected in ["fö.bar", "renamed.bar"] {
+        let entry_reader = zip_reader.entry_reader().await.expect("failed to read entry").expect("no entry");
+        assert_eq!(expected, entry_reader.entry().name());
+        entry_reader.read_to_end_crc().await.expect("failed to read entry");
+    }
+}
+
+#[tokio::test]
+async fn estimated_size_matches_output() {
+    use tokio::io::AsyncWriteExt;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+    zip_writer.comment("archive comment".to_string());
+
+    let opts = EntryOptions::new("foo.bar".to_string(), Compression::Stored).comment("entry comment".to_string());
+    zip_writer.write_entry_whole(opts, &[0xAA; 1000]).await.expect("failed to write entry");
+
+    let opts = EntryOptions::new("bar.foo".to_string(), Compression::Stored).extra_field(0xCAFE, vec![1, 2, 3]);
+    let mut entry_writer = zip_writer.write_entry_stream(opts).await.expect("failed to open write entry");
+    entry_writer.write_all(&[0xBB; 500]).await.expect("failed to write entry");
+    entry_writer.close().await.expect("failed to close entry");
+
+    // Local headers and data, data descriptor, central directory headers, and the end of central directory header.
+    let expected = (30 + 7 + 1000) + (30 + 7 + 7 + 500 + 16) + (46 + 7 + 13) + (46 + 7 + 7) + (22 + 15);
+    assert_eq!(expected, zip_writer.estimated_size());
+
+    let estimated = zip_writer.estimated_size();
+    zip_writer.close().await.expect("failed to close writer");
+    assert_eq!(estimated, input_stream.into_inner().len() as u64);
+}
+
+#[tokio::test]
+async fn overrunning_extra_field_length() {
+    use crate::error::ZipError;
+    use crate::read::seek::ZipFileReader;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    for name in ["foo.bar", "bar.foo"] {
+        let opts = EntryOptions::new(name.to_string(), Compression::Stored).extra_field(0xCAFE, vec![0; 4]);
+        zip_writer.write_entry_whole(opts, b"foo bar").await.expect("failed to write entry");
+    }
+
+    // Claim an extra field far longer than the eight bytes actually written for the first entry.
+    zip_writer.cd_entries[0].header.extra_field_length = 0x4000;
+    zip_writer.close().await.expect("failed to close writer");
+
+    input_stream.set_position(0);
+    let result = ZipFileReader::new(&mut input_stream).await;
+    assert!(matches!(result, Err(ZipError::InvalidArchive(_))));
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn seek_within_stored_entry() {
+    use crate::error::ZipError;
+    use crate::read::seek::ZipFileReader;
+    use std::io::SeekFrom;
+    use tokio::io::AsyncReadExt;
+
+    let data: Vec<u8> = (0..100).collect();
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    let opts = EntryOptions::new("foo.bar".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, &data).await.expect("failed to write entry");
+    let opts = EntryOptions::new("bar.foo".to_string(), Compression::Deflate);
+    zip_writer.write_entry_whole(opts, &data).await.expect("failed to write entry");
+    zip_writer.close().await.expect("failed to close writer");
+
+    input_stream.set_position(0);
+    let mut zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+    let mut entry_reader = zip_reader.entry_reader(0).await.expect("failed to open entry reader");
+    let mut buffer = [0; 4];
+
+    assert_eq!(10, entry_reader.seek_stored(SeekFrom::Start(10)).await.expect("failed to seek"));
+    entry_reader.read_exact(&mut buffer).await.expect("failed to read entry");
+    assert_eq!([10, 11, 12, 13], buffer);
+
+    assert_eq!(12, entry_reader.seek_stored(SeekFrom::Current(-2)).await.expect("failed to seek"));
+    entry_reader.read_exact(&mut buffer).await.expect("failed to read entry");
+    assert_eq!([12, 13, 14, 15], buffer);
+
+    assert_eq!(96, entry_reader.seek_stored(SeekFrom::End(-4)).await.expect("failed to seek"));
+    entry_reader.read_exact(&mut buffer).await.expect("failed to read entry");
+    assert_eq!([96, 97, 98, 99], buffer);
+
+    // Seeking beyond either boundary clamps to it.
+    assert_eq!(100, entry_reader.seek_stored(SeekFrom::Start(1000)).await.expect("failed to seek"));
+    assert_eq!(0, entry_reader.read(&mut buffer).await.expect("failed to read entry"));
+    assert_eq!(100, entry_reader.seek_stored(SeekFrom::End(10)).await.expect("failed to seek"));
+    assert_eq!(0, entry_reader.seek_stored(SeekFrom::Current(-1000)).await.expect("failed to seek"));
+
+    assert_eq!(data, entry_reader.read_to_end_crc().await.expect("failed to read entry"));
+
+    let mut entry_reader = zip_reader.entry_reader(1).await.expect("failed to open entry reader");
+    let result = entry_reader.seek_stored(SeekFrom::Start(10)).await;
+    assert!(matches!(result, Err(ZipError::NotSeekable)));
+}
+
+#[tokio::test]
+async fn generated_index_entry() {
+    use crate::read::mem::ZipFileReader;
+    use tokio::io::AsyncWriteExt;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream).with_index("INDEX.txt");
+
+    let opts = EntryOptions::new("foo.txt".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, b"foo").await.expect("failed to write entry");
+
+    let opts = EntryOptions::new("dir/bar.txt".to_string(), Compression::Stored);
+    let mut entry_writer = zip_writer.write_entry_stream(opts).await.expect("failed to open write entry");
+    entry_writer.write_all(b"bar bar").await.expect("failed to write entry");
+    entry_writer.close().await.expect("failed to close entry");
+    zip_writer.close().await.expect("failed to close writer");
+
+    let data = input_stream.into_inner();
+    let mut zip_reader = ZipFileReader::new(&data).await.expect("failed to open reader");
+    assert_eq!(3, zip_reader.entries().len());
+
+    let (index, _) = zip_reader.entry("INDEX.txt").expect("missing index entry");
+    assert_eq!(2, index);
+
+    let expected: String = zip_reader.entries()[..2]
+        .iter()
+        .map(|entry| {
+            format!("{:08x} {} {}\n", entry.crc32().unwrap(), entry.uncompressed_size().unwrap(), entry.name())
+        })
+        .collect();
+
+    let entry_reader = zip_reader.entry_reader(index).await.expect("failed to open entry reader");
+    let listing = entry_reader.read_to_string_crc().await.expect("failed to read index");
+
+    assert_eq!(expected, listing);
+    assert!(listing.starts_with("8c736521 3 foo.txt\n"));
+}
+
+#[tokio::test]
+async fn gap_before_central_directory() {
+    use crate::read::seek::ZipFileReader;
+    use tokio::io::AsyncWriteExt;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    let opts = EntryOptions::new("foo.bar".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, b"foo").await.expect("failed to write entry");
+    let opts = EntryOptions::new("bar.foo".to_string(), Compression::Stored);
+    let mut entry_writer = zip_writer.write_entry_stream(opts).await.expect("failed to open write entry");
+    entry_writer.write_all(b"bar").await.expect("failed to write entry");
+    entry_writer.close().await.expect("failed to close entry");
+    zip_writer.close().await.expect("failed to close writer");
+
+    let archive = input_stream.into_inner();
+    let mut zip_reader = ZipFileReader::new(Cursor::new(archive.clone())).await.expect("failed to open reader");
+    assert_eq!(0, zip_reader.gap_before_central_directory().await.expect("failed to compute gap"));
+
+    // Inject bytes before the central directory and shift its recorded offset to match.
+    let length = archive.len();
+    let cent_dir_offset = u32::from_le_bytes(archive[length - 6..length - 2].try_into().unwrap());
+    let mut injected = archive[..cent_dir_offset as usize].to_vec();
+    injected.extend_from_slice(&[0xAA; 37]);
+    injected.extend_from_slice(&archive[cent_dir_offset as usize..]);
+    let length = injected.len();
+    injected[length - 6..length - 2].copy_from_slice(&(cent_dir_offset + 37).to_le_bytes());
+
+    let mut zip_reader = ZipFileReader::new(Cursor::new(injected)).await.expect("failed to open reader");
+    assert_eq!(37, zip_reader.gap_before_central_directory().await.expect("failed to compute gap"));
+
+    // A writer may use ZIP64 for a small entry, in which case its data descriptor holds eight-byte sizes.
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    let opts = EntryOptions::new("foo.bar".to_string(), Compression::Stored).extra_field(0x0001, vec![0; 16]);
+    let mut entry_writer = zip_writer.write_entry_stream(opts).await.expect("failed to open write entry");
+    entry_writer.write_all(b"foo").await.expect("failed to write entry");
+    entry_writer.close().await.expect("failed to close entry");
+    zip_writer.close().await.expect("failed to close writer");
+
+    // Widen the descriptor's sizes and shift the central directory's recorded offset to match.
+    let archive = input_stream.into_inner();
+    let length = archive.len();
+    let cent_dir_offset = u32::from_le_bytes(archive[length - 6..length - 2].try_into().unwrap()) as usize;
+    let sizes = cent_dir_offset - 8;
+    let mut widened = archive[..sizes].to_vec();
+    for size in [&archive[sizes..sizes + 4], &archive[sizes + 4..cent_dir_offset]] {
+        widened.extend_from_slice(&(u32::from_le_bytes(size.try_into().unwrap()) as u64).to_le_bytes());
+    }
+    widened.extend_from_slice(&archive[cent_dir_offset..]);
+    let length = widened.len();
+    widened[length - 6..length - 2].copy_from_slice(&(cent_dir_offset as u32 + 8).to_le_bytes());
+
+    let mut zip_reader = ZipFileReader::new(Cursor::new(widened)).await.expect("failed to open reader");
+    assert_eq!(0, zip_reader.gap_before_central_directory().await.expect("failed to compute gap"));
+}
+
+// Encrypt data with the traditional PKWARE encryption method, prefixed by an encryption header with the given check.
+fn zipcrypto_encrypt(password: &[u8], check: u8, data: &[u8]) -> Vec<u8> {
+    let mut keys = crate::read::zipcrypto::ZipCryptoKeys::new(password);
+    let mut plaintext = vec![0x5A; 11];
+    plaintext.push(check);
+    plaintext.extend_from_slice(data);
+
+    plaintext
+        .into_iter()
+        .map(|byte| {
+            let encrypted = byte ^ keys.stream_byte();
+            keys.update(byte);
+            encrypted
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn zipcrypto_encrypted_entries() {
+    use crate::error::ZipError;
+    use crate::read::seek::ZipFileReader;
+
+    let data = b"Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt...";
+    let mut methods = vec![Compression::Stored];
+    #[cfg(feature = "deflate")]
+    methods.push(Compression::Deflate);
+
+    // Write each entry normally in order to obtain its compressed bytes.
+    let mut plain_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut plain_stream);
+    for (index, compression) in methods.iter().enumerate() {
+        let opts = EntryOptions::new(index.to_string(), *compression);
+        zip_writer.write_entry_whole(opts, data).await.expect("failed to write entry");
+    }
+    zip_writer.close().await.expect("failed to close writer");
+
+    plain_stream.set_position(0);
+    let mut plain_reader = ZipFileReader::new(&mut plain_stream).await.expect("failed to open reader");
+    let crc = plain_reader.entries()[0].crc32().unwrap();
+
+    // Rewrite the compressed bytes encrypted, and forge the central directory to describe the original entries.
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+    for (index, compression) in methods.iter().enumerate() {
+        let (reader, size) = plain_reader.seek_to_entry(index).await.expect("failed to seek to entry");
+        let mut compressed = vec![0; size as usize];
+        tokio::io::AsyncReadExt::read_exact(reader, &mut compressed).await.expect("failed to read entry");
+
+        let encrypted = zipcrypto_encrypt(b"password", (crc >> 24) as u8, &compressed);
+        let opts = EntryOptions::new(index.to_string(), Compression::Stored);
+        zip_writer.write_entry_whole(opts, &encrypted).await.expect("failed to write entry");
+
+        let header = &mut zip_writer.cd_entries[index].header;
+        header.flags.encrypted = true;
+        header.compression = compression.to_u16();
+        header.crc = crc;
+        header.uncompressed_size = data.len() as u32;
+    }
+    zip_writer.close().await.expect("failed to close writer");
+
+    input_stream.set_position(0);
+    let mut zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+
+    for index in 0..methods.len() {
+        assert!(zip_reader.entries()[index].encrypted());
+
+        let entry_reader = zip_reader.entry_reader_with_password(index, b"password").await.expect("failed to open");
+        assert_eq!(&data[..], &entry_reader.read_to_end_crc().await.expect("failed to read entry")[..]);
+
+        let result = zip_reader.entry_reader_with_password(index, b"incorrect").await;
+        assert!(matches!(result, Err(ZipError::IncorrectPassword)));
+
+        let result = zip_reader.entry_reader(index).await;
+        assert!(matches!(result, Err(ZipError::FeatureNotSupported(_))));
+    }
+}
+
+#[tokio::test]
+async fn zipcrypto_time_check_byte() {
+    use crate::read::seek::ZipFileReader;
+
+    let data = b"Hello, world!";
+    // 23:45:10 alongside an invalid (zero) date, neither of which survive a round trip through a chrono value.
+    let mod_time: u16 = (23 << 11) | (45 << 5) | 5;
+    let encrypted = zipcrypto_encrypt(b"password", (mod_time >> 8) as u8, data);
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+    let opts = EntryOptions::new("foo.bar".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, &encrypted).await.expect("failed to write entry");
+
+    // An entry with a data descriptor may be checked against its modification time rather than its CRC32 value.
+    let header = &mut zip_writer.cd_entries[0].header;
+    header.flags.encrypted = true;
+    header.flags.data_descriptor = true;
+    header.crc = crc32fast::hash(data);
+    header.uncompressed_size = data.len() as u32;
+    header.mod_time = mod_time;
+    header.mod_date = 0;
+    zip_writer.close().await.expect("failed to close writer");
+
+    input_stream.set_position(0);
+    let mut zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+
+    let entry_reader = zip_reader.entry_reader_with_password(0, b"password").await.expect("failed to open");
+    assert_eq!(&data[..], &entry_reader.read_to_end_crc().await.expect("failed to read entry")[..]);
+}
+
+// A source which is only ready on every other poll, and records the furthest offset read from it.
+struct SlowSource {
+    inner: Cursor<Vec<u8>>,
+    furthest: std::sync::Arc<std::sync::atomic::AtomicU64>,
+    ready: bool,
+}
+
+impl tokio::io::AsyncRead for SlowSource {
+    fn poll_read(
+        mut self: std::pin::Pin<&mut Self>,
+        c: &mut std::task::Context<'_>,
+        b: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        self.ready = !self.ready;
+        if !self.ready {
+            c.waker().wake_by_ref();
+            return std::task::Poll::Pending;
+        }
+
+        let poll = tokio::io::AsyncRead::poll_read(std::pin::Pin::new(&mut self.inner), c, b);
+        self.furthest.fetch_max(self.inner.position(), std::sync::atomic::Ordering::SeqCst);
+        poll
+    }
+}
+
+impl tokio::io::AsyncSeek for SlowSource {
+    fn start_seek(mut self: std::pin::Pin<&mut Self>, position: std::io::SeekFrom) -> std::io::Result<()> {
+        tokio::io::AsyncSeek::start_seek(std::pin::Pin::new(&mut self.inner), position)
+    }
+
+    fn poll_complete(
+        mut self: std::pin::Pin<&mut Self>,
+        c: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<u64>> {
+        tokio::io::AsyncSeek::poll_complete(std::pin::Pin::new(&mut self.inner), c)
+    }
+}
+
+#[tokio::test]
+async fn entries_stream_reads_incrementally() {
+    use crate::read::seek::ZipFileReader;
+    use crate::read::ReaderOptions;
+    use futures_util::StreamExt;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    let names = ["foo.bar", "bar.foo", "baz.qux"];
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    for name in names {
+        let opts = EntryOptions::new(name.to_string(), Compression::Stored);
+        zip_writer.write_entry_whole(opts, b"Hello, world!").await.expect("failed to write entry");
+    }
+    zip_writer.close().await.expect("failed to close writer");
+
+    let archive = input_stream.into_inner();
+    let length = archive.len();
+    let cent_dir_offset = u32::from_le_bytes(archive[length - 6..length - 2].try_into().unwrap()) as u64;
+
+    let furthest = Arc::new(AtomicU64::new(0));
+    let mut source = SlowSource { inner: Cursor::new(archive), furthest: furthest.clone(), ready: false };
+    let stream = ZipFileReader::entries_stream(&mut source, ReaderOptions::default()).await.expect("failed to open");
+    futures_util::pin_mut!(stream);
+
+    // Discard the reads of the end of central directory header, which sits after the central directory.
+    furthest.store(0, Ordering::SeqCst);
+
+    // Each central directory header is 46 bytes plus the length of its name, and is only read once it's polled for.
+    for (index, name) in names.iter().enumerate() {
+        let entry = stream.next().await.expect("stream ended early").expect("failed to read entry");
+        assert_eq!(*name, entry.name());
+
+        let read_until = cent_dir_offset + (index as u64 + 1) * (46 + 7);
+        assert_eq!(read_until, furthest.load(Ordering::SeqCst));
+    }
+    assert!(stream.next().await.is_none());
+}
+
+#[cfg(windows)]
+#[tokio::test]
+async fn extract_restores_creation_time() {
+    use crate::read::seek::ZipFileReader;
+    use crate::spec::extra_field::NTFS;
+
+    // 2020-01-01 00:00:00 UTC, as the number of 100 nanosecond intervals since January 1, 1601.
+    let created: u64 = 132_223_104_000_000_000;
+
+    let mut ntfs = vec![0; 4];
+    ntfs.extend_from_slice(&1u16.to_le_bytes());
+    ntfs.extend_from_slice(&24u16.to_le_bytes());
+    ntfs.extend_from_slice(&[0; 16]);
+    ntfs.extend_from_slice(&created.to_le_bytes());
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    let opts = EntryOptions::new("created.txt".to_string(), Compression::Stored).extra_field(NTFS, ntfs);
+    zip_writer.write_entry_whole(opts, b"Hello, world!").await.expect("failed to write entry");
+    zip_writer.close().await.expect("failed to close writer");
+
+    let mut zip_reader = ZipFileReader::new(input_stream).await.expect("failed to open reader");
+    let expected = zip_reader.entries()[0].created().expect("missing creation time");
+    assert_eq!(1_577_836_800, expected.timestamp());
+
+    let directory = std::env::temp_dir().join(format!("async_zip_extract_creation_time_{}", std::process::id()));
+    zip_reader.extract_to(&directory).await.expect("failed to extract");
+
+    let metadata = tokio::fs::metadata(directory.join("created.txt")).await.expect("failed to read metadata");
+    assert_eq!(std::time::SystemTime::from(expected), metadata.created().expect("failed to read creation time"));
+
+    tokio::fs::remove_dir_all(&directory).await.expect("failed to remove temporary directory");
+}
+
+#[tokio::test]
+async fn try_entry_found_and_missing() {
+    use crate::error::ZipError;
+    use crate::read::mem::ZipFileReader;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    for name in ["foo.bar", "bar.foo"] {
+        let opts = EntryOptions::new(name.to_string(), Compression::Stored);
+        zip_writer.write_entry_whole(opts, b"Hello, world!").await.expect("failed to write entry");
+    }
+    zip_writer.close().await.expect("failed to close writer");
+
+    let zip_reader = ZipFileReader::new(input_stream.into_inner()).await.expect("failed to open reader");
+
+    let (index, entry) = zip_reader.try_entry("bar.foo").expect("failed to find entry");
+    assert_eq!(1, index);
+    assert_eq!("bar.foo", entry.name());
+
+    let result = zip_reader.try_entry("missing.txt");
+    assert!(matches!(result, Err(ZipError::EntryNotFound(name)) if name == "missing.txt"));
+}
+
+#[tokio::test]
+async fn buffer_cap_steers_to_streaming() {
+    use crate::error::ZipError;
+    use crate::read::seek::ZipFileReader;
+
+    let data = vec![b'a'; 1024 * 1024];
+    // Each character is two bytes wide, so one straddles the end of the bytes read when the cap is exceeded.
+    let text = "é".repeat(512 * 1024);
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    let opts = EntryOptions::new("large.txt".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, &data).await.expect("failed to write entry");
+    let opts = EntryOptions::new("large_utf8.txt".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, text.as_bytes()).await.expect("failed to write entry");
+    zip_writer.close().await.expect("failed to close writer");
+
+    input_stream.set_position(0);
+    let mut zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+
+    let entry_reader = zip_reader.entry_reader(0).await.expect("failed to open entry reader").buffer_cap(64 * 1024);
+    let result = entry_reader.read_to_end_crc().await;
+    assert!(matches!(result, Err(ZipError::EntryTooLargeToBuffer(65536))));
+
+    let entry_reader = zip_reader.entry_reader(0).await.expect("failed to open entry reader").buffer_cap(64 * 1024);
+    let result = entry_reader.read_to_string_crc().await;
+    assert!(matches!(result, Err(ZipError::EntryTooLargeToBuffer(65536))));
+
+    let entry_reader = zip_reader.entry_reader(1).await.expect("failed to open entry reader").buffer_cap(64 * 1024);
+    let result = entry_reader.read_to_string_crc().await;
+    assert!(matches!(result, Err(ZipError::EntryTooLargeToBuffer(65536))));
+
+    let entry_reader = zip_reader.entry_reader(1).await.expect("failed to open entry reader");
+    assert_eq!(text, entry_reader.read_to_string_crc().await.expect("failed to read entry"));
+
+    // The cap is also enforced on the bytes read where the recorded size understates the entry.
+    zip_reader.entries[0].uncompressed_size = Some(1024);
+    let entry_reader = zip_reader.entry_reader(0).await.expect("failed to open entry reader").buffer_cap(64 * 1024);
+    let result = entry_reader.read_to_end_crc().await;
+    assert!(matches!(result, Err(ZipError::EntryTooLargeToBuffer(65536))));
+    zip_reader.entries[0].uncompressed_size = Some(data.len() as u64);
+
+    // Streaming only holds a single buffer in memory, so it's unaffected by the cap.
+    let entry_reader = zip_reader.entry_reader(0).await.expect("failed to open entry reader").buffer_cap(64 * 1024);
+    let mut output = Vec::new();
+    entry_reader.copy_to_end_crc(&mut output, 8192).await.expect("failed to stream entry");
+    assert_eq!(data, output);
+
+    let entry_reader = zip_reader.entry_reader(0).await.expect("failed to open entry reader");
+    assert_eq!(data, entry_reader.read_to_end_crc().await.expect("failed to read entry"));
+
+    // A recorded size just within the cap doesn't preallocate a buffer of that size up front.
+    zip_reader.entries[0].uncompressed_size = Some(crate::read::DEFAULT_BUFFER_CAP - 1);
+    let entry_reader = zip_reader.entry_reader(0).await.expect("failed to open entry reader");
+    let buffer = entry_reader.read_to_end_crc().await.expect("failed to read entry");
+    assert_eq!(data, buffer);
+    assert!(buffer.capacity() < 4 * data.len());
+}
+
+#[tokio::test]
+async fn duplicate_content_detection() {
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream).with_duplicate_detection();
+
+    let entries: [(&str, &[u8]); 6] = [
+        ("a.txt", b"first"),
+        ("b.txt", b"second"),
+        ("c.txt", b"first"),
+        ("empty1.txt", b""),
+        ("empty2.txt", b""),
+        ("d.txt", b"second"),
+    ];
+
+    for (name, data) in entries {
+        let opts = EntryOptions::new(name.to_string(), Compression::Stored);
+        zip_writer.write_entry_whole(opts, data).await.expect("failed to write entry");
+    }
+
+    let expected = [("c.txt".to_string(), "a.txt".to_string()), ("d.txt".to_string(), "b.txt".to_string())];
+    assert_eq!(&expected[..], zip_writer.duplicate_content_report());
+
+    // Duplicates are still written in full.
+    assert_eq!(6, zip_writer.cd_entries.len());
+    zip_writer.close().await.expect("failed to close writer");
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+    for name in ["a.txt", "b.txt"] {
+        let opts = EntryOptions::new(name.to_string(), Compression::Stored);
+        zip_writer.write_entry_whole(opts, b"same").await.expect("failed to write entry");
+    }
+    assert!(zip_writer.duplicate_content_report().is_empty());
+}
+
+#[tokio::test]
+async fn prefer_utf8_filename_heuristic() {
+    use crate::read::mem::ZipFileReader;
+    use crate::read::ReaderOptions;
+
+    // "Привет" encoded as IBM866 (a DOS code page), which isn't valid UTF-8.
+    const IBM866_NAME: [u8; 6] = [0x8F, 0xE0, 0xA8, 0xA2, 0xA5, 0xE2];
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    for name in ["readme.txt", "naïve.txt", "abcdef.txt"] {
+        let opts = EntryOptions::new(name.to_string(), Compression::Stored);
+        zip_writer.write_entry_whole(opts, b"Hello, world!").await.expect("failed to write entry");
+    }
+
+    // Clear the UTF-8 flag from the central directory, as set by the writer for the non-ASCII name.
+    zip_writer.cd_entries[1].header.flags.filename_unicode = false;
+    zip_writer.close().await.expect("failed to close writer");
+
+    let mut archive = input_stream.into_inner();
+    while let Some(position) = archive.windows(6).position(|window| window == b"abcdef") {
+        archive[position..position + 6].copy_from_slice(&IBM866_NAME);
+    }
+
+    let options = ReaderOptions::new().filename_encoding(encoding_rs::IBM866);
+    let zip_reader = ZipFileReader::with_options(&archive, options.clone()).await.expect("failed to open reader");
+    assert_eq!("readme.txt", zip_reader.entries()[0].name());
+    assert_ne!("naïve.txt", zip_reader.entries()[1].name());
+    assert_eq!("Привет.txt", zip_reader.entries()[2].name());
+
+    let zip_reader = ZipFileReader::with_options(&archive, options.clone().prefer_utf8(true)).await.expect("failed");
+    assert_eq!("readme.txt", zip_reader.entries()[0].name());
+    assert_eq!("naïve.txt", zip_reader.entries()[1].name());
+    assert_eq!("Привет.txt", zip_reader.entries()[2].name());
+
+    // Clear the UTF-8 flag from the local file headers too, and then read them from a non-seekable source.
+    let signature = crate::spec::signature::LOCAL_FILE_HEADER.to_le_bytes();
+    let positions: Vec<_> = archive.windows(4).enumerate().filter(|(_, w)| *w == signature).map(|(i, _)| i).collect();
+    for position in positions {
+        archive[position + 7] &= !0x08;
+    }
+
+    async fn stream_names(archive: &[u8], options: ReaderOptions) -> Vec<String> {
+        let mut zip_reader = crate::read::stream::ZipFileReader::with_options(Cursor::new(archive), options);
+        let mut names = Vec::new();
+
+        while let Some(entry_reader) = zip_reader.entry_reader().await.expect("failed to read entry") {
+            names.push(entry_reader.entry().name().to_string());
+            entry_reader.read_to_end_crc().await.expect("failed to read entry");
+        }
+        names
+    }
+
+    let names = stream_names(&archive, options.clone()).await;
+    assert_ne!("naïve.txt", names[1]);
+    assert_eq!("Привет.txt", names[2]);
+
+    let names = stream_names(&archive, options.prefer_utf8(true)).await;
+    assert_eq!(vec!["readme.txt", "naïve.txt", "Привет.txt"], names);
+}
+
+#[tokio::test]
+async fn reopen_after_source_replaced() {
+    use crate::read::seek::ZipFileReader;
+
+    async fn archive(names: &[&str], comment: &str) -> Vec<u8> {
+        let mut input_stream = Cursor::new(Vec::<u8>::new());
+        let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+        for name in names {
+            let opts = EntryOptions::new(name.to_string(), Compression::Stored);
+            zip_writer.write_entry_whole(opts, b"Hello, world!").await.expect("failed to write entry");
+        }
+        zip_writer.comment(comment.to_string());
+        zip_writer.close().await.expect("failed to close writer");
+
+        input_stream.into_inner()
+    }
+
+    let source = Cursor::new(archive(&["foo.bar"], "first").await);
+    let mut zip_reader = ZipFileReader::new(source).await.expect("failed to open reader");
+    assert_eq!(1, zip_reader.entries().len());
+
+    *zip_reader.reader.get_mut() = archive(&["foo.bar", "bar.foo"], "second").await;
+    zip_reader.reopen().await.expect("failed to reopen reader");
+    assert_eq!(2, zip_reader.entries().len());
+    assert_eq!("bar.foo", zip_reader.entries()[1].name());
+    assert_eq!(Some("second"), zip_reader.comment());
+
+    let entry_reader = zip_reader.entry_reader(1).await.expect("failed to open entry reader");
+    assert_eq!("Hello, world!", entry_reader.read_to_string_crc().await.expect("failed to read entry"));
+
+    // An invalid replacement leaves the previous state intact.
+    *zip_reader.reader.get_mut() = vec![0; 64];
+    assert!(zip_reader.reopen().await.is_err());
+    assert_eq!(2, zip_reader.entries().len());
+    assert_eq!(Some("second"), zip_reader.comment());
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn zlib_wrapped_deflate_entry() {
+    use crate::read::seek::ZipFileReader;
+    use tokio::io::AsyncWriteExt;
+
+    let data = b"Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt...";
+
+    let mut encoder = async_compression::tokio::write::ZlibEncoder::new(Vec::new());
+    encoder.write_all(data).await.expect("failed to compress data");
+    encoder.shutdown().await.expect("failed to compress data");
+    let wrapped = encoder.into_inner();
+    assert_eq!(0x78, wrapped[0]);
+
+    // Write the zlib stream as-is, and forge the central directory to declare it as Deflate.
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    let opts = EntryOptions::new("zlib.txt".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, &wrapped).await.expect("failed to write entry");
+    let opts = EntryOptions::new("deflate.txt".to_string(), Compression::Deflate);
+    zip_writer.write_entry_whole(opts, data).await.expect("failed to write entry");
+
+    let header = &mut zip_writer.cd_entries[0].header;
+    header.compression = Compression::Deflate.to_u16();
+    header.crc = crc32fast::hash(data);
+    header.uncompressed_size = data.len() as u32;
+    zip_writer.close().await.expect("failed to close writer");
+
+    input_stream.set_position(0);
+    let mut zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+
+    for index in 0..2 {
+        let entry_reader = zip_reader.entry_reader(index).await.expect("failed to open entry reader");
+        assert_eq!(&data[..], &entry_reader.read_to_end_crc().await.expect("failed to read entry")[..]);
+    }
+
+    // The zlib header must still be detected when the source returns fewer bytes per read than are needed to tell.
+    let source = OneByteSource(Cursor::new(input_stream.into_inner()));
+    let mut zip_reader = ZipFileReader::new(source).await.expect("failed to open reader");
+
+    for index in 0..2 {
+        let entry_reader = zip_reader.entry_reader(index).await.expect("failed to open entry reader");
+        assert_eq!(&data[..], &entry_reader.read_to_end_crc().await.expect("failed to read entry")[..]);
+    }
+}
+
+// A source which returns at most a single byte per read.
+struct OneByteSource(Cursor<Vec<u8>>);
+
+impl tokio::io::AsyncRead for OneByteSource {
+    fn poll_read(
+        mut self: std::pin::Pin<&mut Self>,
+        c: &mut std::task::Context<'_>,
+        b: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        if b.remaining() == 0 {
+            return std::task::Poll::Ready(Ok(()));
+        }
+
+        let mut byte = [0; 1];
+        let mut read_buf = tokio::io::ReadBuf::new(&mut byte);
+        let poll = tokio::io::AsyncRead::poll_read(std::pin::Pin::new(&mut self.0), c, &mut read_buf);
+        b.put_slice(read_buf.filled());
+        poll
+    }
+}
+
+impl tokio::io::AsyncSeek for OneByteSource {
+    fn start_seek(mut self: std::pin::Pin<&mut Self>, position: std::io::SeekFrom) -> std::io::Result<()> {
+        tokio::io::AsyncSeek::start_seek(std::pin::Pin::new(&mut self.0), position)
+    }
+
+    fn poll_complete(
+        mut self: std::pin::Pin<&mut Self>,
+        c: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<u64>> {
+        tokio::io::AsyncSeek::poll_complete(std::pin::Pin::new(&mut self.0), c)
+    }
+}
+
+#[tokio::test]
+async fn raw_extra_preserved() {
+    use crate::read::mem::ZipFileReader;
+
+    // Two records in an unusual order, followed by trailing bytes which don't form a complete record.
+    let extra = vec![0xEF, 0xBE, 0x02, 0x00, 0xAA, 0xBB, 0x01, 0xCA, 0x00, 0x00, 0x12, 0x34, 0x56];
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    let opts = EntryOptions::new("extra.txt".to_string(), Compression::Stored).extra(extra.clone());
+    zip_writer.write_entry_whole(opts, b"Hello, world!").await.expect("failed to write entry");
+    let opts = EntryOptions::new("plain.txt".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, b"Hello, world!").await.expect("failed to write entry");
+    zip_writer.close().await.expect("failed to close writer");
+
+    let zip_reader = ZipFileReader::new(input_stream.get_ref()).await.expect("failed to open reader");
+    let entry = &zip_reader.entries()[0];
+
+    assert_eq!(Some(&extra[..]), entry.raw_extra());
+
+    let fields = entry.extra_fields();
+    assert_eq!(2, fields.len());
+    assert_eq!(0xBEEF, fields[0].header_id());
+    assert_eq!(0xCA01, fields[1].header_id());
+
+    assert!(zip_reader.entries()[1].raw_extra().map_or(true, <[u8]>::is_empty));
+}
+
+#[tokio::test]
+async fn modified_system_time_round_trip() {
+    use crate::read::seek::ZipFileReader;
+    use crate::spec::extra_field::EXTENDED_TIMESTAMP;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    // 2021-06-15 13:45:31 UTC and 1975-03-01 12:00:00 UTC.
+    let times = [1_623_764_731, 162_907_200];
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    for (index, time) in times.iter().enumerate() {
+        let modified = UNIX_EPOCH + Duration::from_secs(*time);
+        let opts = EntryOptions::new(index.to_string(), Compression::Stored).modified(modified);
+        zip_writer.write_entry_whole(opts, b"Hello, world!").await.expect("failed to write entry");
+    }
+    zip_writer.close().await.expect("failed to close writer");
+
+    input_stream.set_position(0);
+    let zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+
+    // The MS-DOS format has a two second precision, and can't represent years before 1980 (ie. 1980-01-01 00:00:00).
+    assert_eq!(1_623_764_730, zip_reader.entries()[0].last_modified().timestamp());
+    assert_eq!(315_532_800, zip_reader.entries()[1].last_modified().timestamp());
+
+    // The extended timestamp holds the exact time regardless.
+    for (index, time) in times.iter().enumerate() {
+        let fields = zip_reader.entries()[index].extra_fields();
+        let field = fields.iter().find(|field| field.header_id() == EXTENDED_TIMESTAMP).expect("missing timestamp");
+
+        assert_eq!(0x01, field.data()[0]);
+        assert_eq!(*time as i32, i32::from_le_bytes(field.data()[1..5].try_into().unwrap()));
+    }
+}
+
+#[tokio::test]
+async fn find_entries_by_extension() {
+    use crate::read::mem::ZipFileReader;
+
+    let names = ["a.txt", "b.png", "notes/c.TXT", "d.txt.bak", "txt", "日本.txt", "dir.txt/"];
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    for name in names {
+        let opts = EntryOptions::new(name.to_string(), Compression::Stored);
+        zip_writer.write_entry_whole(opts, b"Hello, world!").await.expect("failed to write entry");
+    }
+    zip_writer.close().await.expect("failed to close writer");
+
+    let zip_reader = ZipFileReader::new(input_stream.get_ref()).await.expect("failed to open reader");
+
+    let found = |entries: Vec<(usize, &crate::read::ZipEntry)>| entries.iter().map(|(i, _)| *i).collect::<Vec<_>>();
+    assert_eq!(vec![0, 5], found(zip_reader.find_by_extension("txt")));
+    assert_eq!(vec![0, 2, 5], found(zip_reader.find_by_extension_ignore_case("txt")));
+    assert_eq!(vec![0, 2, 5], found(zip_reader.find_by_extension_ignore_case("TXT")));
+    assert!(zip_reader.find_by_extension("jpg").is_empty());
+}
+
+#[tokio::test]
+async fn nested_archive() {
+    use crate::read::seek::ZipFileReader;
+
+    let mut inner_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut inner_stream);
+    let opts = EntryOptions::new("inner.txt".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, b"Hello from the inside!").await.expect("failed to write entry");
+    zip_writer.close().await.expect("failed to close writer");
+
+    let mut methods = vec![Compression::Stored];
+    #[cfg(feature = "deflate")]
+    methods.push(Compression::Deflate);
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+    for (index, compression) in methods.iter().enumerate() {
+        let opts = EntryOptions::new(format!("{}.zip", index), *compression);
+        zip_writer.write_entry_whole(opts, inner_stream.get_ref()).await.expect("failed to write entry");
+    }
+    let opts = EntryOptions::new("not_a.zip".to_string(), Compression::Stored);
+    zip_writer.write_entry_whole(opts, b"Hello, world!").await.expect("failed to write entry");
+    zip_writer.close().await.expect("failed to close writer");
+
+    input_stream.set_position(0);
+    let mut zip_reader = ZipFileReader::new(&mut input_stream).await.expect("failed to open reader");
+
+    for index in 0..methods.len() {
+        let mut nested = zip_reader.nested(index).await.expect("failed to open nested archive");
+        assert_eq!(1, nested.entries().len());
+
+        let entry_reader = nested.entry_reader(0).await.expect("failed to open entry reader");
+        assert_eq!("Hello from the inside!", entry_reader.read_to_string_crc().await.expect("failed to read entry"));
+    }
+
+    assert!(zip_reader.nested(methods.len()).await.is_err());
+}
+
+#[tokio::test]
+async fn extract_converts_text_line_endings() {
+    use crate::read::seek::ZipFileReader;
+    use crate::read::ExtractOptions;
+
+    let data = b"one\r\ntwo\nthree\r\r\nend\r";
+    #[cfg(windows)]
+    let expected = b"one\r\ntwo\r\nthree\r\r\nend\r";
+    #[cfg(not(windows))]
+    let expected = b"one\ntwo\nthree\r\nend\r";
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    for name in ["text.txt", "binary.dat"] {
+        let opts = EntryOptions::new(name.to_string(), Compression::Stored);
+        zip_writer.write_entry_whole(opts, data).await.expect("failed to write entry");
+    }
+    zip_writer.cd_entries[0].header.inter_attr = 0x1;
+    zip_writer.close().await.expect("failed to close writer");
+
+    let mut zip_reader = ZipFileReader::new(input_stream).await.expect("failed to open reader");
+    assert!(zip_reader.entries()[0].is_text());
+    assert!(!zip_reader.entries()[1].is_text());
+
+    let base = std::env::temp_dir().join(format!("async_zip_extract_text_line_endings_{}", std::process::id()));
+
+    let options = ExtractOptions::new().convert_text_line_endings(true);
+    zip_reader.extract_to_with_options(&base.join("converted"), options).await.expect("failed to extract");
+    assert_eq!(&expected[..], &tokio::fs::read(base.join("converted/text.txt")).await.expect("failed to read")[..]);
+    assert_eq!(&data[..], &tokio::fs::read(base.join("converted/binary.dat")).await.expect("failed to read")[..]);
+
+    zip_reader.extract_to(&base.join("unconverted")).await.expect("failed to extract");
+    assert_eq!(&data[..], &tokio::fs::read(base.join("unconverted/text.txt")).await.expect("failed to read")[..]);
+
+    tokio::fs::remove_dir_all(&base).await.expect("failed to remove temporary directory");
+}
+
+#[tokio::test]
+async fn header_errors_report_structure_and_offset() {
+    use crate::error::ZipError;
+    use crate::read::seek::{read_cd_entry, ZipFileReader};
+    use crate::read::ReaderOptions;
+    use crate::spec::signature::{CENTRAL_DIRECTORY_FILE_HEADER, LOCAL_FILE_HEADER};
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    for name in ["first.txt", "second.txt"] {
+        let opts = EntryOptions::new(name.to_string(), Compression::Stored);
+        zip_writer.write_entry_whole(opts, b"data").await.expect("failed to write entry");
+    }
+    zip_writer.close().await.expect("failed to close writer");
+
+    let data = input_stream.into_inner();
+    let find = |signature: u32| -> Vec<usize> {
+        let signature = signature.to_le_bytes();
+        data.windows(4).enumerate().filter(|(_, window)| *window == signature).map(|(index, _)| index).collect()
+    };
+    let (local_offset, cd_offset) = (find(LOCAL_FILE_HEADER)[1], find(CENTRAL_DIRECTORY_FILE_HEADER)[1]);
+
+    // A corrupted central directory record is reported at its own offset rather than that of the directory.
+    let mut corrupted = data.clone();
+    corrupted[cd_offset..cd_offset + 4].copy_from_slice(&0xDEADBEEFu32.to_le_bytes());
+    match ZipFileReader::new(Cursor::new(corrupted)).await {
+        Err(ZipError::UnexpectedHeaderAt(name, offset, actual)) => {
+            assert_eq!("central directory file header", name);
+            assert_eq!(cd_offset as u64, offset);
+            assert_eq!(0xDEADBEEF, actual);
+        }
+        _ => panic!("expected an unexpected header error"),
+    }
+
+    // A corrupted local file header is only found once the entry is opened.
+    let mut corrupted = data.clone();
+    corrupted[local_offset..local_offset + 4].copy_from_slice(&0x01020304u32.to_le_bytes());
+    let mut zip_reader = ZipFileReader::new(Cursor::new(corrupted)).await.expect("failed to open reader");
+    assert!(zip_reader.entry_reader(0).await.is_ok());
+    match zip_reader.entry_reader(1).await {
+        Err(ZipError::UnexpectedHeaderAt(name, offset, actual)) => {
+            assert_eq!("local file header", name);
+            assert_eq!(local_offset as u64, offset);
+            assert_eq!(0x01020304, actual);
+        }
+        _ => panic!("expected an unexpected header error"),
+    }
+
+    // A record which ends before its fixed-size fields is reported as truncated.
+    let record = data[cd_offset..cd_offset + 20].to_vec();
+    match read_cd_entry(&mut Cursor::new(record), &ReaderOptions::default(), 0x40, &mut u64::MAX).await {
+        Err(ZipError::TruncatedHeader(name, offset)) => {
+            assert_eq!("central directory file header", name);
+            assert_eq!(0x40, offset);
+        }
+        _ => panic!("expected a truncated header error"),
+    }
+
+    // A source without an end of central directory record reports the range which was scanned for it.
+    match ZipFileReader::new(Cursor::new(vec![0; 100])).await {
+        Err(ZipError::HeaderNotFound(name, start, end)) => {
+            assert_eq!("end of central directory record", name);
+            assert_eq!((0, 100), (start, end));
+        }
+        _ => panic!("expected a header not found error"),
+    }
+}
+
+#[tokio::test]
+async fn stream_header_errors_report_structure_and_offset() {
+    use crate::error::ZipError;
+    use crate::read::stream::ZipFileReader;
+    use crate::spec::signature::LOCAL_FILE_HEADER;
+    use tokio::io::AsyncWriteExt;
+
+    let mut input_stream = Cursor::new(Vec::<u8>::new());
+    let mut zip_writer = ZipFileWriter::new(&mut input_stream);
+
+    // The first entry's end is found by reading beyond it, so those bytes mustn't be counted towards the position.
+    let opts = EntryOptions::new("first.txt".to_string(), Compression::Stored);
+    let mut entry_writer = zip_writer.write_entry_stream(opts).await.expect("failed to open write entry");
+    entry_writer.write_all(b"data").await.expect("failed to write entry");
+    entry_writer.close().await.expect("failed to close entry");
+    for name in ["second.txt", "third.txt"] {
+        let opts = EntryOptions::new(name.to_string(), Compression::Stored);
+        zip_writer.write_entry_whole(opts, b"data").await.expect("failed to write entry");
+    }
+    zip_writer.close().await.expect("failed to close writer");
+
+    // The third entry's header is corrupted, as the first entry's end is found via the second entry's signature.
+    let data = input_stream.into_inner();
+    let signature = LOCAL_FILE_HEADER.to_le_bytes();
+    let offset = data.windows(4).enumerate().filter(|(_, window)| *window == signature).nth(2).unwrap().0;
+
+    let mut corrupted = data.clone();
+    corrupted[offset..offset + 4].copy_from_slice(&0x01020304u32.to_le_bytes());
+    let mut zip_reader = ZipFileReader::new(Cursor::new(corrupted));
+
+    for _ in 0..2 {
+        let entry_reader = zip_reader.entry_reader().await.expect("failed to open entry reader").unwrap();
+        entry_reader.read_to_end_crc().await.expect("failed to read entry");
+    }
+
+    match zip_reader.entry_reader().await {
+        Err(ZipError::UnexpectedHeaderAt(name, actual_offset, actual)) => {
+            assert_eq!("local file header", name);
+            assert_eq!(offset as u64, actual_offset);
+            assert_eq!(0x01020304, actual);
+        }
+        _ => panic!("expected an unexpected header error"),
+    }
+
+    // A source which ends partway through a header reports it as truncated.
+    let mut zip_reader = ZipFileReader::new(Cursor::new(data[..offset + 10].to_vec()));
+
+    for _ in 0..2 {
+        let entry_reader = zip_reader.entry_reader().await.expect("failed to open entry reader").unwrap();
+        entry_reader.read_to_end_crc().await.expect("failed to read entry");
+    }
+
+    match zip_reader.entry_reader().await {
+        Err(ZipError::TruncatedHeader(name, actual_offset)) => {
+            assert_eq!("local file header", name);
+            assert_eq!(offset as u64, actual_offset);
+        }
+        _ => panic!("expected a truncated header error"),
+    }
+}
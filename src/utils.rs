@@ -4,10 +4,26 @@
 use crate::error::{Result, ZipError};
 use tokio::io::{AsyncRead, AsyncReadExt};
 
-// Assert that the next four-byte signature read by a reader which impls AsyncRead matches the expected signature.
-pub(crate) async fn assert_signature<R: AsyncRead + Unpin>(reader: &mut R, expected: u32) -> Result<()> {
-    match reader.read_u32_le().await? {
+// Assert that the next four-byte signature read by a reader which impls AsyncRead matches the expected signature. The
+// offset is that of the signature within the source, so that a mismatch reports which structure was expected and where.
+pub(crate) async fn assert_signature_at<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    expected: u32,
+    offset: u64,
+) -> Result<()> {
+    match reader.read_u32_le().await.map_err(|err| truncated_at(expected, offset)(err.into()))? {
         actual if actual == expected => Ok(()),
-        actual => Err(ZipError::UnexpectedHeaderError(actual, expected)),
+        actual => Err(ZipError::UnexpectedHeaderAt(crate::spec::signature::name(expected), offset, actual)),
+    }
+}
+
+// Returns a mapping which attributes a read of a header which ended early to the structure being read (as identified
+// by its signature) and the offset of its signature within the source. Any other error is left as-is.
+pub(crate) fn truncated_at(signature: u32, offset: u64) -> impl FnOnce(ZipError) -> ZipError {
+    move |err| match err {
+        ZipError::UpstreamReadError(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => {
+            ZipError::TruncatedHeader(crate::spec::signature::name(signature), offset)
+        }
+        err => err,
     }
 }
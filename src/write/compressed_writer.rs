@@ -46,6 +46,9 @@ impl<'b, W: AsyncWrite + Unpin> CompressedAsyncWriter<'b, W> {
             }
             #[cfg(feature = "xz")]
             Compression::Xz => CompressedAsyncWriter::Xz(write::XzEncoder::new(ShutdownIgnoredWriter { 0: writer })),
+            // Rejected by EntryOptions::validate() before any writer is constructed.
+            #[cfg(feature = "legacy")]
+            Compression::Shrink | Compression::Reduce(_) => unreachable!(),
         }
     }
 
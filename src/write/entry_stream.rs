@@ -30,6 +30,7 @@ pub struct EntryStreamWriter<'b, W: AsyncWrite + Unpin> {
     lfh: LocalFileHeader,
     lfh_offset: usize,
     data_offset: usize,
+    version_made_by: u16,
 }
 
 impl<'b, W: AsyncWrite + Unpin> EntryStreamWriter<'b, W> {
@@ -40,15 +41,26 @@ impl<'b, W: AsyncWrite + Unpin> EntryStreamWriter<'b, W> {
         let lfh_offset = writer.writer.offset();
         let lfh = EntryStreamWriter::write_lfh(writer, &options).await?;
         let data_offset = writer.writer.offset();
+        let version_made_by = writer.version_made_by;
 
         let cd_entries = &mut writer.cd_entries;
         let writer = AsyncOffsetWriter::new(CompressedAsyncWriter::from_raw(&mut writer.writer, options.compression));
 
-        Ok(EntryStreamWriter { writer, cd_entries, options, lfh, lfh_offset, data_offset, hasher: Hasher::new() })
+        Ok(EntryStreamWriter {
+            writer,
+            cd_entries,
+            options,
+            lfh,
+            lfh_offset,
+            data_offset,
+            version_made_by,
+            hasher: Hasher::new(),
+        })
     }
 
     async fn write_lfh(writer: &'b mut ZipFileWriter<W>, options: &EntryOptions) -> Result<LocalFileHeader> {
-        let (mod_time, mod_date) = crate::spec::date::chrono_to_zip_time(&Utc::now());
+        let (mod_time, mod_date) =
+            crate::spec::date::chrono_to_zip_time(&options.last_modified.unwrap_or_else(Utc::now));
 
         let lfh = LocalFileHeader {
             compressed_size: 0,
@@ -63,6 +75,7 @@ impl<'b, W: AsyncWrite + Unpin> EntryStreamWriter<'b, W> {
             flags: GeneralPurposeFlag {
                 data_descriptor: true,
                 encrypted: false,
+                compression_options: 0,
                 filename_unicode: !options.filename.is_ascii(),
             },
         };
@@ -84,7 +97,9 @@ impl<'b, W: AsyncWrite + Unpin> EntryStreamWriter<'b, W> {
     /// - Pushing that central directory header to the [`ZipFileWriter`]'s store.
     ///
     /// Failiure to call this function before going out of scope would result in a corrupted ZIP file.
-    pub async fn close(mut self) -> Result<()> {
+    ///
+    /// Returns the offset at which the entry's local file header was written.
+    pub async fn close(mut self) -> Result<u64> {
         self.writer.shutdown().await?;
 
         let crc = self.hasher.finalize();
@@ -101,7 +116,7 @@ impl<'b, W: AsyncWrite + Unpin> EntryStreamWriter<'b, W> {
             compressed_size,
             uncompressed_size,
             crc,
-            v_made_by: crate::spec::version::as_made_by(),
+            v_made_by: self.version_made_by,
             v_needed: self.lfh.version,
             compression: self.lfh.compression,
             extra_field_length: self.lfh.extra_field_length,
@@ -117,7 +132,7 @@ impl<'b, W: AsyncWrite + Unpin> EntryStreamWriter<'b, W> {
         };
 
         self.cd_entries.push(CentralDirectoryEntry { header: cdh, opts: self.options });
-        Ok(())
+        Ok(self.lfh_offset as u64)
     }
 }
 
@@ -26,10 +26,17 @@ impl<'b, 'c, W: AsyncWrite + Unpin> EntryWholeWriter<'b, 'c, W> {
         Self { writer, opts, data }
     }
 
-    pub async fn write(self) -> Result<()> {
+    pub async fn write(mut self) -> Result<u64> {
+        if self.data.is_empty() {
+            self.opts.compression = Compression::Stored;
+        }
+
         let mut _compressed_data: Option<Vec<u8>> = None;
         let compressed_data = match &self.opts.compression {
             Compression::Stored => self.data,
+            // Rejected by EntryOptions::validate() before any writer is constructed.
+            #[cfg(feature = "legacy")]
+            Compression::Shrink | Compression::Reduce(_) => unreachable!(),
             #[cfg(any(feature = "deflate", feature = "bzip2", feature = "zstd", feature = "lzma", feature = "xz"))]
             _ => {
                 _compressed_data = Some(compress(&self.opts.compression, self.data).await);
@@ -37,7 +44,9 @@ impl<'b, 'c, W: AsyncWrite + Unpin> EntryWholeWriter<'b, 'c, W> {
             }
         };
 
-        let (mod_time, mod_date) = crate::spec::date::chrono_to_zip_time(&Utc::now());
+        let (mod_time, mod_date) =
+            crate::spec::date::chrono_to_zip_time(&self.opts.last_modified.unwrap_or_else(Utc::now));
+        let lh_offset = self.writer.writer.offset() as u64;
 
         let lf_header = LocalFileHeader {
             compressed_size: compressed_data.len() as u32,
@@ -52,12 +61,13 @@ impl<'b, 'c, W: AsyncWrite + Unpin> EntryWholeWriter<'b, 'c, W> {
             flags: GeneralPurposeFlag {
                 data_descriptor: false,
                 encrypted: false,
+                compression_options: 0,
                 filename_unicode: !self.opts.filename.is_ascii(),
             },
         };
 
         let header = CentralDirectoryHeader {
-            v_made_by: crate::spec::version::as_made_by(),
+            v_made_by: self.writer.version_made_by,
             v_needed: lf_header.version,
             compressed_size: lf_header.compressed_size,
             uncompressed_size: lf_header.uncompressed_size,
@@ -72,7 +82,7 @@ impl<'b, 'c, W: AsyncWrite + Unpin> EntryWholeWriter<'b, 'c, W> {
             disk_start: 0,
             inter_attr: 0,
             exter_attr: self.opts.unix_permissions << 16,
-            lh_offset: self.writer.writer.offset() as u32,
+            lh_offset: lh_offset as u32,
         };
 
         self.writer.writer.write_all(&crate::spec::signature::LOCAL_FILE_HEADER.to_le_bytes()).await?;
@@ -83,7 +93,7 @@ impl<'b, 'c, W: AsyncWrite + Unpin> EntryWholeWriter<'b, 'c, W> {
 
         self.writer.cd_entries.push(CentralDirectoryEntry { header, opts: self.opts });
 
-        Ok(())
+        Ok(lh_offset)
     }
 }
 
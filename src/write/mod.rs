@@ -56,12 +56,17 @@ pub(crate) mod entry_whole;
 
 pub use entry_stream::EntryStreamWriter;
 
-use crate::error::Result;
+use crate::error::{Result, ZipError};
 use crate::spec::compression::Compression;
+use crate::spec::extra_field::EXTENDED_TIMESTAMP;
 use crate::spec::header::{CentralDirectoryHeader, EndOfCentralDirectoryHeader};
+use crate::spec::version::HostSystem;
 use async_io_utilities::AsyncOffsetWriter;
 use entry_whole::EntryWholeWriter;
 
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::time::SystemTime;
 use tokio::io::{AsyncWrite, AsyncWriteExt};
 
 /// A set of options for opening new ZIP entries.
@@ -71,12 +76,20 @@ pub struct EntryOptions {
     extra: Vec<u8>,
     comment: String,
     unix_permissions: u32,
+    pub(crate) last_modified: Option<DateTime<Utc>>,
 }
 
 impl EntryOptions {
     /// Construct a new set of options from its required constituents.
     pub fn new(filename: String, compression: Compression) -> Self {
-        EntryOptions { filename, compression, extra: Vec::new(), comment: String::new(), unix_permissions: 0 }
+        EntryOptions {
+            filename,
+            compression,
+            extra: Vec::new(),
+            comment: String::new(),
+            unix_permissions: 0,
+            last_modified: None,
+        }
     }
 
     /// Consume the options and override the extra field data.
@@ -85,17 +98,61 @@ impl EntryOptions {
         self
     }
 
+    /// Consume the options and append an extra field record with the given header ID and data.
+    ///
+    /// Multiple records may be added by calling this method repeatedly. The records are written to both the local file
+    /// header and the central directory header, and the total length of all extra field data must not exceed 65535
+    /// bytes (else [`ZipError::ExtraFieldTooLarge`] will be returned when writing the entry).
+    pub fn extra_field(mut self, header_id: u16, data: Vec<u8>) -> Self {
+        crate::spec::extra_field::push_extra_field(&mut self.extra, header_id, &data);
+        self
+    }
+
     /// Consume the options and override the file comment.
     pub fn comment(mut self, comment: String) -> Self {
         self.comment = comment;
         self
     }
 
+    /// Consume the options and set the entry's last modification time (defaulting to the time it's written).
+    ///
+    /// The headers store the time in the MS-DOS format, which only has a two second precision and can't represent
+    /// years before 1980 or after 2107, so such times are clamped to the nearest representable time. The exact time
+    /// (to the second) is also appended as an extended timestamp extra field record, as long as it fits within the
+    /// record's signed 32-bit Unix time. Overriding the extra field data afterwards discards this record.
+    pub fn modified(mut self, modified: SystemTime) -> Self {
+        let modified = DateTime::<Utc>::from(modified);
+
+        if let Ok(timestamp) = i32::try_from(modified.timestamp()) {
+            // A flags byte indicating that only the modification time is present, followed by that time.
+            let mut data = vec![0x01];
+            data.extend_from_slice(&timestamp.to_le_bytes());
+            crate::spec::extra_field::push_extra_field(&mut self.extra, EXTENDED_TIMESTAMP, &data);
+        }
+
+        self.last_modified = Some(modified);
+        self
+    }
+
     /// Consume unix permissions option for zip files (ex. 0o755)
     pub fn unix_permissions(mut self, unix_permissions: u32) -> Self {
         self.unix_permissions = unix_permissions;
         self
     }
+
+    // Ensure all variable-length fields fit within the lengths which are able to be stored within the headers.
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.extra.len() > u16::MAX as usize {
+            return Err(ZipError::ExtraFieldTooLarge);
+        }
+
+        #[cfg(feature = "legacy")]
+        if matches!(self.compression, Compression::Shrink | Compression::Reduce(_)) {
+            return Err(ZipError::FeatureNotSupported("Writing Shrink or Reduce compressed entries"));
+        }
+
+        Ok(())
+    }
 }
 
 pub(crate) struct CentralDirectoryEntry {
@@ -103,6 +160,19 @@ pub(crate) struct CentralDirectoryEntry {
     pub opts: EntryOptions,
 }
 
+// The lengths of a central directory file header and an end of central directory header (including their signatures)
+// without any variable-length fields.
+const CENTRAL_DIRECTORY_ENTRY_LENGTH: u64 = 46;
+const END_OF_CENTRAL_DIRECTORY_LENGTH: u64 = 22;
+
+// The data of each distinct entry written whole so far, keyed by its CRC32 value and size, alongside the duplicates
+// found of it.
+#[derive(Default)]
+struct ContentCache {
+    contents: HashMap<(u32, u64), Vec<(String, Vec<u8>)>>,
+    duplicates: Vec<(String, String)>,
+}
+
 /// A ZIP file writer which acts over AsyncWrite implementers.
 ///
 /// # Note
@@ -111,29 +181,160 @@ pub struct ZipFileWriter<W: AsyncWrite + Unpin> {
     pub(crate) writer: AsyncOffsetWriter<W>,
     pub(crate) cd_entries: Vec<CentralDirectoryEntry>,
     comment_opt: Option<String>,
+    pub(crate) version_made_by: u16,
+    index_path: Option<String>,
+    content_cache: Option<ContentCache>,
 }
 
 impl<W: AsyncWrite + Unpin> ZipFileWriter<W> {
     /// Construct a new ZIP file writer from a mutable reference to a writer.
     pub fn new(writer: W) -> Self {
-        Self { writer: AsyncOffsetWriter::new(writer), cd_entries: Vec::new(), comment_opt: None }
+        Self {
+            writer: AsyncOffsetWriter::new(writer),
+            cd_entries: Vec::new(),
+            comment_opt: None,
+            version_made_by: crate::spec::version::as_made_by(),
+            index_path: None,
+            content_cache: None,
+        }
+    }
+
+    /// Consume the writer and enable the generation of an index entry at the provided path.
+    ///
+    /// Upon [`ZipFileWriter::close()`], a stored entry is written at the path (after all other entries) which lists
+    /// every other entry on its own line, in the order written, as its CRC32 value (in hexadecimal), its uncompressed
+    /// size, and its filename, separated by single spaces. For example:
+    ///
+    /// ```text
+    /// 8c736521 3 foo.txt
+    /// ```
+    pub fn with_index(mut self, path: &str) -> Self {
+        self.index_path = Some(path.to_string());
+        self
+    }
+
+    /// Consume the writer and enable the detection of entries whose data is identical to an entry written before.
+    ///
+    /// ZIP has no means of sharing data between entries, so duplicates are still written in full, but they're
+    /// recorded within [`ZipFileWriter::duplicate_content_report()`] so that the caller can decide how to handle them.
+    ///
+    /// # Note
+    /// Data is only compared for non-empty entries written via [`ZipFileWriter::write_entry_whole()`]. Candidates are
+    /// found by their CRC32 value and size and then compared in full, so a copy of each distinct entry's data is held
+    /// in memory until the writer is closed.
+    pub fn with_duplicate_detection(mut self) -> Self {
+        self.content_cache = Some(ContentCache::default());
+        self
+    }
+
+    /// Returns the entries found to have data identical to an entry written before them, in the order written.
+    ///
+    /// Each is given as its filename alongside the filename of the first entry written with the same data. This is
+    /// always empty unless enabled via [`ZipFileWriter::with_duplicate_detection()`].
+    pub fn duplicate_content_report(&self) -> &[(String, String)] {
+        match &self.content_cache {
+            Some(cache) => &cache.duplicates,
+            None => &[],
+        }
     }
 
     /// Write a new ZIP entry of known size and data.
-    pub async fn write_entry_whole(&mut self, options: EntryOptions, data: &[u8]) -> Result<()> {
-        EntryWholeWriter::from_raw(self, options, data).write().await
+    ///
+    /// If the data is empty, the entry is always written as [`Compression::Stored`] regardless of the requested
+    /// compression method, as compressing nothing only adds overhead (and some strict readers reject it).
+    ///
+    /// Returns the offset at which the entry's local file header was written.
+    pub async fn write_entry_whole(&mut self, options: EntryOptions, data: &[u8]) -> Result<u64> {
+        options.validate()?;
+        let offset = EntryWholeWriter::from_raw(self, options, data).write().await?;
+
+        if let (Some(cache), Some(entry)) = (&mut self.content_cache, self.cd_entries.last()) {
+            if !data.is_empty() {
+                let filename = entry.opts.filename.clone();
+                let candidates = cache.contents.entry((entry.header.crc, data.len() as u64)).or_default();
+
+                match candidates.iter().find(|(_, candidate)| candidate == data) {
+                    Some((original, _)) => cache.duplicates.push((filename, original.clone())),
+                    None => candidates.push((filename, data.to_vec())),
+                }
+            }
+        }
+
+        Ok(offset)
     }
 
     /// Write an entry of unknown size and data via streaming (ie. using a data descriptor).
     pub async fn write_entry_stream(&mut self, options: EntryOptions) -> Result<EntryStreamWriter<'_, W>> {
+        options.validate()?;
         EntryStreamWriter::from_raw(self, options).await
     }
 
+    /// Flushes all bytes written so far through to the underlying writer.
+    ///
+    /// Each entry's local file header and data are written to the underlying writer as soon as the entry is complete,
+    /// so calling this method after each entry allows a consumer (eg. a client of a streaming download) to receive
+    /// the archive incrementally. The central directory is still only written upon [`ZipFileWriter::close()`].
+    pub async fn flush(&mut self) -> Result<()> {
+        self.writer.flush().await?;
+        Ok(())
+    }
+
+    /// Returns the total size of the archive which would be produced if [`ZipFileWriter::close()`] were called now.
+    ///
+    /// This is the sum of every local file header and data (plus data descriptor) written so far, their central
+    /// directory headers, and the end of central directory header and ZIP file comment. Each entry is written in full
+    /// before its writer returns, so this is exact for all entries added so far, regardless of compression.
+    ///
+    /// # Note
+    /// The size of an entry which hasn't yet been written can only be known in advance if it's [`Compression::Stored`],
+    /// in which case it'll occupy 30 bytes, plus its filename and extra field lengths, plus its data length (and a
+    /// further 16 bytes if streamed), in addition to 46 bytes plus its filename, extra field, and comment lengths
+    /// within the central directory. An index entry (see [`ZipFileWriter::with_index()`]) isn't accounted for, as it's
+    /// only generated and written upon [`ZipFileWriter::close()`].
+    pub fn estimated_size(&self) -> u64 {
+        let entries = self.writer.offset() as u64;
+        let cent_dir: u64 = self
+            .cd_entries
+            .iter()
+            .map(|entry| {
+                let variable = entry.opts.filename.len() + entry.opts.extra.len() + entry.opts.comment.len();
+                CENTRAL_DIRECTORY_ENTRY_LENGTH + variable as u64
+            })
+            .sum();
+        let comment = self.comment_opt.as_ref().map(|comment| comment.len()).unwrap_or_default() as u64;
+
+        entries + cent_dir + END_OF_CENTRAL_DIRECTORY_LENGTH + comment
+    }
+
+    // Generate the contents of an index entry, listing every entry written so far.
+    fn index(&self) -> String {
+        let mut index = String::new();
+
+        for entry in &self.cd_entries {
+            let header = &entry.header;
+            index.push_str(&format!("{:08x} {} {}\n", header.crc, header.uncompressed_size, entry.opts.filename));
+        }
+
+        index
+    }
+
     /// Set the ZIP file comment.
     pub fn comment(&mut self, comment: String) {
         self.comment_opt = Some(comment);
     }
 
+    /// Set the host system and specification version recorded as having made all subsequently written entries.
+    ///
+    /// The version is stored as the major version multiplied by ten plus the minor version (eg. 63 for 6.3). By
+    /// default, entries are recorded as having been made by a Unix host supporting version 6.3 of the specification.
+    ///
+    /// # Note
+    /// Readers only interpret Unix permissions (see [`EntryOptions::unix_permissions()`]) for entries made by a
+    /// Unix-like host, so permissions set on entries made by any other host are likely to be ignored.
+    pub fn set_version_made_by(&mut self, host: HostSystem, version: u8) {
+        self.version_made_by = crate::spec::version::with_host(host, version);
+    }
+
     /// Consumes this ZIP writer and completes all closing tasks.
     ///
     /// This includes:
@@ -143,6 +344,11 @@ impl<W: AsyncWrite + Unpin> ZipFileWriter<W> {
     ///
     /// Failiure to call this function before going out of scope would result in a corrupted ZIP file.
     pub async fn close(mut self) -> Result<()> {
+        if let Some(path) = self.index_path.take() {
+            let index = self.index();
+            self.write_entry_whole(EntryOptions::new(path, Compression::Stored), index.as_bytes()).await?;
+        }
+
         let cd_offset = self.writer.offset();
 
         for entry in &self.cd_entries {